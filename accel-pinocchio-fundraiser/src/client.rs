@@ -0,0 +1,548 @@
+//! Off-chain client for the fundraiser program, behind the `client` feature: PDA derivation,
+//! instruction builders for every `FundRaiserInstrctions` variant and account decoders
+//! (`FundRaiser::from_bytes`, `Contributor::from_bytes`, ...), so callers never hand-roll the
+//! wire format.
+//!
+//! Builders return the fixed accounts of each instruction; transfer-hook extra accounts for
+//! Token-2022 mints are appended by the caller.
+
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use crate::instructions::FundRaiserInstrctions;
+pub use crate::state::{
+    config::Config,
+    contributor::Contributor,
+    fundraiser::{ContributionCapKind, FundRaiser, FundRaiserPhase, FundRaiserStatus},
+    milestones::{Milestones, Tranche},
+    settlement::Settlement,
+};
+
+pub const PROGRAM_ID: Pubkey = Pubkey::new_from_array(crate::ID);
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array(pinocchio_system::ID);
+pub const TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array(pinocchio_token::ID);
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array(pinocchio_associated_token_account::ID);
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey = Pubkey::new_from_array(crate::constants::BPF_LOADER_UPGRADEABLE_ID);
+
+pub fn fundraiser_address(maker: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fundraiser", maker.as_ref(), &campaign_id.to_le_bytes()], &PROGRAM_ID)
+}
+
+/// Address of a campaign created by the unversioned program, which had no campaign ids.
+pub fn legacy_fundraiser_address(maker: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"fundraiser", maker.as_ref()], &PROGRAM_ID)
+}
+
+pub fn contributor_address(fundraiser: &Pubkey, contributor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"contributor", fundraiser.as_ref(), contributor.as_ref()], &PROGRAM_ID)
+}
+
+/// Lamport vault of a native SOL campaign.
+pub fn native_vault_address(fundraiser: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"vault", fundraiser.as_ref()], &PROGRAM_ID)
+}
+
+pub fn milestones_address(fundraiser: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"milestones", fundraiser.as_ref()], &PROGRAM_ID)
+}
+
+pub fn receipt_mint_address(fundraiser: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt", fundraiser.as_ref()], &PROGRAM_ID)
+}
+
+/// Where a stretch-goal campaign records the tier it settled at.
+pub fn settlement_address(fundraiser: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"settlement", fundraiser.as_ref()], &PROGRAM_ID)
+}
+
+pub fn config_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &PROGRAM_ID)
+}
+
+/// The loader's ProgramData account, holding the upgrade authority allowed to create the config.
+pub fn program_data_address() -> Pubkey {
+    Pubkey::find_program_address(&[PROGRAM_ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+}
+
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    ).0
+}
+
+/// Everything needed to address one campaign's accounts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Campaign {
+    pub maker: Pubkey,
+    pub campaign_id: u64,
+    /// `None` for native SOL campaigns.
+    pub mint: Option<Pubkey>,
+    /// Legacy SPL Token or Token-2022; ignored for native SOL campaigns.
+    pub token_program: Pubkey,
+    /// The `FundRaiser::FLAG_*` bits the campaign is (or will be) initialized with.
+    pub flags: u8,
+    /// Migrated from the unversioned program: the fundraiser lives at `legacy_fundraiser_address`.
+    pub legacy_address: bool,
+}
+
+impl Campaign {
+    /// Rebuilds the campaign from its decoded state; the token program is the mint's owner.
+    pub fn from_state(state: &FundRaiser, token_program: Pubkey) -> Self {
+        Self {
+            maker: Pubkey::new_from_array(state.maker()),
+            campaign_id: state.campaign_id(),
+            mint: (!state.is_native()).then(|| Pubkey::new_from_array(state.mint_to_raise())),
+            token_program,
+            flags: state.flags(),
+            legacy_address: state.has_legacy_address(),
+        }
+    }
+
+    pub fn fundraiser(&self) -> Pubkey {
+        if self.legacy_address {
+            legacy_fundraiser_address(&self.maker).0
+        } else {
+            fundraiser_address(&self.maker, self.campaign_id).0
+        }
+    }
+
+    pub fn vault(&self) -> Pubkey {
+        let fundraiser = self.fundraiser();
+        match self.mint {
+            Some(mint) => associated_token_address(&fundraiser, &mint, &self.token_program),
+            None => native_vault_address(&fundraiser).0,
+        }
+    }
+
+    pub fn contributor_account(&self, contributor: &Pubkey) -> Pubkey {
+        contributor_address(&self.fundraiser(), contributor).0
+    }
+
+    /// Receipts of `contributor`, held in their ATA for the receipt mint.
+    pub fn receipt_account(&self, contributor: &Pubkey) -> Pubkey {
+        associated_token_address(contributor, &receipt_mint_address(&self.fundraiser()).0, &self.token_program)
+    }
+
+    // Native SOL campaigns have no mint or token accounts: those slots take placeholders
+    fn mint_or_placeholder(&self) -> Pubkey {
+        self.mint.unwrap_or(SYSTEM_PROGRAM_ID)
+    }
+
+    fn token_account_or_wallet(&self, wallet: &Pubkey) -> Pubkey {
+        match self.mint {
+            Some(mint) => associated_token_address(wallet, &mint, &self.token_program),
+            None => *wallet,
+        }
+    }
+
+    // No config, no fee: the fee recipient slots are placeholders
+    fn fee_recipient_accounts(&self, fee_recipient: Option<&Pubkey>) -> (Pubkey, Pubkey) {
+        match fee_recipient {
+            Some(fee_recipient) => (*fee_recipient, self.token_account_or_wallet(fee_recipient)),
+            None => (self.maker, self.maker),
+        }
+    }
+
+    fn has_flag(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+}
+
+/// Initialize arguments; the optional sections are only sent when their flag is set on the campaign.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InitializeArgs {
+    pub amount_to_raise: u64,
+    /// 0 opens the campaign right away.
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub min_contribution: u64,
+    pub max_per_contributor: u64,
+    pub cap_kind: ContributionCapKind,
+    /// 0 for no hard cap.
+    pub hard_cap: u64,
+    /// `(basis_points, unlock_ts)` per tranche, with `FLAG_MILESTONES`.
+    pub tranches: Vec<(u16, i64)>,
+    /// With `FLAG_GOVERNANCE`.
+    pub quorum_bps: u16,
+    /// With `FLAG_GOVERNANCE`.
+    pub approval_threshold_bps: u16,
+    /// With `FLAG_ALLOWLIST`.
+    pub allowlist_root: [u8; 32],
+    /// Ascending thresholds above `amount_to_raise`, with `FLAG_STRETCH_GOALS`.
+    pub stretch_goals: Vec<u64>,
+    /// Lets contributors `withdraw` while the campaign is open; `None` locks contributions in.
+    pub withdrawals: Option<WithdrawTerms>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WithdrawTerms {
+    /// Seconds after their last contribution before a contributor may withdraw.
+    pub cooldown: i64,
+    /// Share of each withdrawal kept in the vault toward the target, below 10000 basis points.
+    pub penalty_bps: u16,
+}
+
+pub fn initialize(campaign: &Campaign, args: &InitializeArgs) -> Instruction {
+    let fundraiser = campaign.fundraiser();
+
+    let mut data = vec![FundRaiserInstrctions::Initialize as u8];
+    data.extend_from_slice(&args.amount_to_raise.to_le_bytes());
+    data.extend_from_slice(&args.start_ts.to_le_bytes());
+    data.extend_from_slice(&args.end_ts.to_le_bytes());
+    data.extend_from_slice(&campaign.campaign_id.to_le_bytes());
+    data.extend_from_slice(&args.min_contribution.to_le_bytes());
+    data.extend_from_slice(&args.max_per_contributor.to_le_bytes());
+    data.push(args.cap_kind as u8);
+    data.extend_from_slice(&args.hard_cap.to_le_bytes());
+    data.push(campaign.flags);
+    if campaign.has_flag(FundRaiser::FLAG_MILESTONES) {
+        data.push(args.tranches.len() as u8);
+        for (basis_points, unlock_ts) in &args.tranches {
+            data.extend_from_slice(&basis_points.to_le_bytes());
+            data.extend_from_slice(&unlock_ts.to_le_bytes());
+        }
+    }
+    if campaign.has_flag(FundRaiser::FLAG_GOVERNANCE) {
+        data.extend_from_slice(&args.quorum_bps.to_le_bytes());
+        data.extend_from_slice(&args.approval_threshold_bps.to_le_bytes());
+    }
+    if campaign.has_flag(FundRaiser::FLAG_ALLOWLIST) {
+        data.extend_from_slice(&args.allowlist_root);
+    }
+    if campaign.has_flag(FundRaiser::FLAG_STRETCH_GOALS) {
+        data.push(args.stretch_goals.len() as u8);
+        for goal in &args.stretch_goals {
+            data.extend_from_slice(&goal.to_le_bytes());
+        }
+    }
+    if let Some(withdrawals) = args.withdrawals {
+        data.extend_from_slice(&withdrawals.cooldown.to_le_bytes());
+        data.extend_from_slice(&withdrawals.penalty_bps.to_le_bytes());
+    }
+
+    let mut accounts = vec![
+        AccountMeta::new(campaign.maker, true),
+        AccountMeta::new_readonly(campaign.mint_or_placeholder(), false),
+        AccountMeta::new(fundraiser, false),
+        AccountMeta::new(campaign.vault(), false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(campaign.token_program, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+    ];
+    if campaign.has_flag(FundRaiser::FLAG_MILESTONES) {
+        accounts.push(AccountMeta::new(milestones_address(&fundraiser).0, false));
+    }
+    if campaign.has_flag(FundRaiser::FLAG_RECEIPT) {
+        accounts.push(AccountMeta::new(receipt_mint_address(&fundraiser).0, false));
+    }
+
+    Instruction { program_id: PROGRAM_ID, accounts, data }
+}
+
+/// Proof that `contributor` may put in up to `max_allocation` on an allowlisted campaign.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AllowlistProof<'a> {
+    pub max_allocation: u64,
+    pub proof: &'a [[u8; 32]],
+}
+
+pub fn contribute(campaign: &Campaign, contributor: &Pubkey, amount: u64, allowlist: Option<AllowlistProof>) -> Instruction {
+    let mut data = vec![FundRaiserInstrctions::Contribute as u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    if let Some(allowlist) = allowlist {
+        data.extend_from_slice(&allowlist.max_allocation.to_le_bytes());
+        data.push(allowlist.proof.len() as u8);
+        for sibling in allowlist.proof {
+            data.extend_from_slice(sibling);
+        }
+    }
+
+    let fundraiser = campaign.fundraiser();
+    let mut accounts = vec![
+        AccountMeta::new(*contributor, true),
+        AccountMeta::new_readonly(campaign.mint_or_placeholder(), false),
+        AccountMeta::new(fundraiser, false),
+        AccountMeta::new(campaign.contributor_account(contributor), false),
+        AccountMeta::new(campaign.token_account_or_wallet(contributor), false),
+        AccountMeta::new(campaign.vault(), false),
+        AccountMeta::new_readonly(campaign.token_program, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    push_receipt_accounts(campaign, &fundraiser, contributor, &mut accounts);
+
+    Instruction { program_id: PROGRAM_ID, accounts, data }
+}
+
+/// Pays out a successful campaign; pass the config's fee recipient once a platform config exists.
+pub fn checker(campaign: &Campaign, fee_recipient: Option<&Pubkey>) -> Instruction {
+    let fundraiser = campaign.fundraiser();
+    let (fee_recipient, fee_recipient_ata) = campaign.fee_recipient_accounts(fee_recipient);
+
+    let mut accounts = vec![
+        AccountMeta::new(campaign.maker, true),
+        AccountMeta::new(campaign.mint_or_placeholder(), false),
+        AccountMeta::new(fundraiser, false),
+        AccountMeta::new(campaign.vault(), false),
+        AccountMeta::new(campaign.token_account_or_wallet(&campaign.maker), false),
+        AccountMeta::new_readonly(campaign.token_program, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(config_address().0, false),
+        AccountMeta::new(fee_recipient, false),
+        AccountMeta::new(fee_recipient_ata, false),
+    ];
+    push_settlement_account(campaign, &fundraiser, &mut accounts);
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: vec![FundRaiserInstrctions::Checker as u8],
+    }
+}
+
+pub fn refund(campaign: &Campaign, contributor: &Pubkey) -> Instruction {
+    let fundraiser = campaign.fundraiser();
+    let mut accounts = vec![
+        AccountMeta::new(*contributor, true),
+        AccountMeta::new_readonly(campaign.maker, false),
+        AccountMeta::new_readonly(campaign.mint_or_placeholder(), false),
+        AccountMeta::new(fundraiser, false),
+        AccountMeta::new(campaign.contributor_account(contributor), false),
+        AccountMeta::new(campaign.token_account_or_wallet(contributor), false),
+        AccountMeta::new(campaign.vault(), false),
+        AccountMeta::new_readonly(campaign.token_program, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    push_receipt_accounts(campaign, &fundraiser, contributor, &mut accounts);
+
+    Instruction { program_id: PROGRAM_ID, accounts, data: vec![FundRaiserInstrctions::Refund as u8] }
+}
+
+/// Takes `amount` back out of an open campaign that allows withdrawals, less any penalty the vault keeps.
+pub fn withdraw(campaign: &Campaign, contributor: &Pubkey, amount: u64) -> Instruction {
+    let mut data = vec![FundRaiserInstrctions::Withdraw as u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let fundraiser = campaign.fundraiser();
+    let mut accounts = vec![
+        AccountMeta::new(*contributor, true),
+        AccountMeta::new_readonly(campaign.maker, false),
+        AccountMeta::new_readonly(campaign.mint_or_placeholder(), false),
+        AccountMeta::new(fundraiser, false),
+        AccountMeta::new(campaign.contributor_account(contributor), false),
+        AccountMeta::new(campaign.token_account_or_wallet(contributor), false),
+        AccountMeta::new(campaign.vault(), false),
+        AccountMeta::new_readonly(campaign.token_program, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    push_receipt_accounts(campaign, &fundraiser, contributor, &mut accounts);
+
+    Instruction { program_id: PROGRAM_ID, accounts, data }
+}
+
+/// Refunds a batch of `contributors` of a failed campaign on their behalf; `keeper` earns a share
+/// of each closed record's rent.
+pub fn crank_refund(campaign: &Campaign, keeper: &Pubkey, contributors: &[Pubkey]) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*keeper, true),
+        AccountMeta::new_readonly(campaign.maker, false),
+        AccountMeta::new_readonly(campaign.mint_or_placeholder(), false),
+        AccountMeta::new(campaign.fundraiser(), false),
+        AccountMeta::new(campaign.vault(), false),
+        AccountMeta::new_readonly(campaign.token_program, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+    ];
+    for contributor in contributors {
+        accounts.push(AccountMeta::new(campaign.contributor_account(contributor), false));
+        accounts.push(AccountMeta::new(*contributor, false));
+        accounts.push(AccountMeta::new(campaign.token_account_or_wallet(contributor), false));
+    }
+
+    Instruction { program_id: PROGRAM_ID, accounts, data: vec![FundRaiserInstrctions::CrankRefund as u8] }
+}
+
+pub fn cancel(campaign: &Campaign) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(campaign.maker, true),
+            AccountMeta::new(campaign.fundraiser(), false),
+        ],
+        data: vec![FundRaiserInstrctions::Cancel as u8],
+    }
+}
+
+/// Tears down a failed campaign once every contribution is refunded, paying the maker any
+/// forfeited withdrawal penalties left in the vault.
+pub fn close(campaign: &Campaign) -> Instruction {
+    let fundraiser = campaign.fundraiser();
+    let mut accounts = vec![
+        AccountMeta::new(campaign.maker, true),
+        AccountMeta::new(campaign.mint_or_placeholder(), false),
+        AccountMeta::new(fundraiser, false),
+        AccountMeta::new(campaign.vault(), false),
+        AccountMeta::new(campaign.token_account_or_wallet(&campaign.maker), false),
+        AccountMeta::new_readonly(campaign.token_program, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+    ];
+    if campaign.has_flag(FundRaiser::FLAG_MILESTONES) {
+        accounts.push(AccountMeta::new(milestones_address(&fundraiser).0, false));
+    }
+
+    Instruction { program_id: PROGRAM_ID, accounts, data: vec![FundRaiserInstrctions::Close as u8] }
+}
+
+pub fn claim_tranche(campaign: &Campaign, fee_recipient: Option<&Pubkey>) -> Instruction {
+    let fundraiser = campaign.fundraiser();
+    let (fee_recipient, fee_recipient_ata) = campaign.fee_recipient_accounts(fee_recipient);
+    let mut accounts = vec![
+        AccountMeta::new(campaign.maker, true),
+        AccountMeta::new(campaign.mint_or_placeholder(), false),
+        AccountMeta::new(fundraiser, false),
+        AccountMeta::new(milestones_address(&fundraiser).0, false),
+        AccountMeta::new(campaign.vault(), false),
+        AccountMeta::new(campaign.token_account_or_wallet(&campaign.maker), false),
+        AccountMeta::new_readonly(campaign.token_program, false),
+        AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        AccountMeta::new_readonly(config_address().0, false),
+        AccountMeta::new(fee_recipient, false),
+        AccountMeta::new(fee_recipient_ata, false),
+    ];
+    push_settlement_account(campaign, &fundraiser, &mut accounts);
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts,
+        data: vec![FundRaiserInstrctions::ClaimTranche as u8],
+    }
+}
+
+pub fn vote(campaign: &Campaign, contributor: &Pubkey, approve: bool) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(*contributor, true),
+            AccountMeta::new(campaign.fundraiser(), false),
+            AccountMeta::new(campaign.contributor_account(contributor), false),
+        ],
+        data: vec![FundRaiserInstrctions::Vote as u8, approve as u8],
+    }
+}
+
+pub fn set_config(admin: &Pubkey, fee_bps: u16, fee_recipient: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    let mut data = vec![FundRaiserInstrctions::SetConfig as u8];
+    data.extend_from_slice(&fee_bps.to_le_bytes());
+    data.extend_from_slice(fee_recipient.as_ref());
+    data.extend_from_slice(new_admin.as_ref());
+
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(config_address().0, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(program_data_address(), false),
+        ],
+        data,
+    }
+}
+
+/// New campaign terms; once contributions are in, only `end_ts` may change and only to a later time.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UpdateArgs {
+    pub amount_to_raise: u64,
+    pub end_ts: i64,
+    pub min_contribution: u64,
+    pub max_per_contributor: u64,
+    pub cap_kind: ContributionCapKind,
+    /// 0 for no hard cap.
+    pub hard_cap: u64,
+}
+
+impl UpdateArgs {
+    /// The campaign's current terms, to change only what needs changing.
+    pub fn from_state(state: &FundRaiser) -> Self {
+        Self {
+            amount_to_raise: state.amount_to_raise(),
+            end_ts: state.end_ts(),
+            min_contribution: state.min_contribution(),
+            max_per_contributor: state.max_per_contributor(),
+            cap_kind: state.cap_kind().unwrap_or(ContributionCapKind::Absolute),
+            hard_cap: state.hard_cap(),
+        }
+    }
+}
+
+pub fn update(campaign: &Campaign, args: &UpdateArgs) -> Instruction {
+    let mut data = vec![FundRaiserInstrctions::Update as u8];
+    data.extend_from_slice(&args.amount_to_raise.to_le_bytes());
+    data.extend_from_slice(&args.end_ts.to_le_bytes());
+    data.extend_from_slice(&args.min_contribution.to_le_bytes());
+    data.extend_from_slice(&args.max_per_contributor.to_le_bytes());
+    data.push(args.cap_kind as u8);
+    data.extend_from_slice(&args.hard_cap.to_le_bytes());
+
+    let fundraiser = campaign.fundraiser();
+    let mut accounts = vec![
+        AccountMeta::new_readonly(campaign.maker, true),
+        AccountMeta::new(fundraiser, false),
+    ];
+    if campaign.has_flag(FundRaiser::FLAG_MILESTONES) {
+        accounts.push(AccountMeta::new_readonly(milestones_address(&fundraiser).0, false));
+    }
+
+    Instruction { program_id: PROGRAM_ID, accounts, data }
+}
+
+/// Upgrades a fundraiser written with an older layout; `payer` covers the extra rent.
+pub fn migrate(payer: &Pubkey, account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: vec![FundRaiserInstrctions::Migrate as u8],
+    }
+}
+
+/// Converts a fundraiser written by the unversioned program to the current layout, in place at
+/// its legacy address; `payer` covers the extra rent. A campaign the old program already paid out is
+/// refused.
+pub fn migrate_legacy_fundraiser(payer: &Pubkey, maker: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Instruction {
+    let fundraiser = legacy_fundraiser_address(maker).0;
+    let mut ix = migrate(payer, &fundraiser);
+    ix.accounts.push(AccountMeta::new_readonly(*mint, false));
+    ix.accounts.push(AccountMeta::new_readonly(*token_program, false));
+    ix.accounts.push(AccountMeta::new_readonly(associated_token_address(&fundraiser, mint, token_program), false));
+    ix
+}
+
+/// Upgrades `contributor`'s record written with an older layout; `payer` covers the extra rent.
+/// Records of a converted unversioned campaign are claimed against its fundraiser, which has to be
+/// converted first.
+pub fn migrate_contributor(payer: &Pubkey, campaign: &Campaign, contributor: &Pubkey) -> Instruction {
+    let mut ix = migrate(payer, &campaign.contributor_account(contributor));
+    ix.accounts.push(if campaign.legacy_address {
+        AccountMeta::new(campaign.fundraiser(), false)
+    } else {
+        AccountMeta::new_readonly(campaign.fundraiser(), false)
+    });
+    ix.accounts.push(AccountMeta::new_readonly(*contributor, false));
+    ix
+}
+
+fn push_settlement_account(campaign: &Campaign, fundraiser: &Pubkey, accounts: &mut Vec<AccountMeta>) {
+    if campaign.has_flag(FundRaiser::FLAG_STRETCH_GOALS) {
+        accounts.push(AccountMeta::new(settlement_address(fundraiser).0, false));
+    }
+}
+
+fn push_receipt_accounts(campaign: &Campaign, fundraiser: &Pubkey, contributor: &Pubkey, accounts: &mut Vec<AccountMeta>) {
+    if campaign.has_flag(FundRaiser::FLAG_RECEIPT) {
+        accounts.push(AccountMeta::new(receipt_mint_address(fundraiser).0, false));
+        accounts.push(AccountMeta::new(campaign.receipt_account(contributor), false));
+    }
+}
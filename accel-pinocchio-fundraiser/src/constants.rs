@@ -1,5 +1,14 @@
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
 pub const MIN_AMOUNT_TO_RAISE: u64 = 3;
-pub const SECONDS_TO_DAYS: i64 = 86400;
-pub const MAX_CONTRIBUTION_PERCENTAGE: u64 = 10;
-pub const PERCENTAGE_SCALER: u64 = 100;
\ No newline at end of file
+pub const BASIS_POINTS_SCALER: u64 = 10_000;
+pub const MAX_TRANCHES: usize = 8;
+pub const MAX_STRETCH_GOALS: usize = 4;
+pub const MAX_PLATFORM_FEE_BPS: u16 = 1_000;
+/// Discriminator and version bytes in front of every versioned account.
+pub const ACCOUNT_HEADER_LEN: usize = 2;
+/// How long contributors have to approve a release before it counts as rejected.
+pub const VOTING_PERIOD: i64 = 7 * 86_400;
+/// Share of a closed contributor record's rent paid to the keeper cranking its refund.
+pub const CRANK_REFUND_BOUNTY_BPS: u64 = 1_000;
+/// Owner of the program's ProgramData account, which records its upgrade authority.
+pub const BPF_LOADER_UPGRADEABLE_ID: pinocchio::pubkey::Pubkey = pinocchio_pubkey::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
@@ -9,7 +9,54 @@ pub enum FundRaiserError {
     MaximumContributionsReached,
     FundraiserNotEnded,
     FundraiserEnded,
-    InvalidAmount
+    InvalidAmount,
+    Unauthorized,
+    FundraiserCancelled,
+    AlreadyRefunded,
+    FundraiserNotStarted,
+    InvalidSchedule,
+    InvalidContributionLimits,
+    InvalidHardCap,
+    HardCapReached,
+    InvalidTokenProgram,
+    InvalidVault,
+    InvalidFundraiser,
+    InvalidMint,
+    InvalidContributorAccount,
+    InvalidMaker,
+    InvalidMilestoneSchedule,
+    InvalidMilestones,
+    TrancheLocked,
+    MilestoneCampaign,
+    InvalidGovernance,
+    GovernanceDisabled,
+    AlreadyVoted,
+    ReleaseNotApproved,
+    ReleaseRejected,
+    InvalidReceiptMint,
+    NotAllowlisted,
+    AllocationExceeded,
+    InvalidConfig,
+    InvalidFee,
+    InvalidFeeRecipient,
+    InvalidAccountVersion,
+    ParametersLocked,
+    DeadlineShortened,
+    InvalidStretchGoals,
+    ReceiptCampaign,
+    InvalidTokenAccount,
+    WithdrawalsDisabled,
+    WithdrawalCooldown,
+    InvalidWithdrawTerms,
+    WithdrawalTooBig,
+    NotUpgradeAuthority,
+    VotingClosed,
+    TransferHookMint,
+    RefundsOutstanding,
+    LegacyAmountExceeded,
+    LegacyVaultDrained,
+    InvalidSettlement,
+    InvalidProgram,
     }
 
 impl From<FundRaiserError> for ProgramError {
@@ -24,6 +71,53 @@ impl From<FundRaiserError> for ProgramError {
             FundRaiserError::FundraiserNotEnded => ProgramError::Custom(1005),
              FundRaiserError::FundraiserEnded => ProgramError::Custom(1006),
             FundRaiserError::InvalidAmount => ProgramError::Custom(1007),
+            FundRaiserError::Unauthorized => ProgramError::Custom(1008),
+            FundRaiserError::FundraiserCancelled => ProgramError::Custom(1009),
+            FundRaiserError::AlreadyRefunded => ProgramError::Custom(1010),
+            FundRaiserError::FundraiserNotStarted => ProgramError::Custom(1011),
+            FundRaiserError::InvalidSchedule => ProgramError::Custom(1012),
+            FundRaiserError::InvalidContributionLimits => ProgramError::Custom(1013),
+            FundRaiserError::InvalidHardCap => ProgramError::Custom(1014),
+            FundRaiserError::HardCapReached => ProgramError::Custom(1015),
+            FundRaiserError::InvalidTokenProgram => ProgramError::Custom(1016),
+            FundRaiserError::InvalidVault => ProgramError::Custom(1017),
+            FundRaiserError::InvalidFundraiser => ProgramError::Custom(1018),
+            FundRaiserError::InvalidMint => ProgramError::Custom(1019),
+            FundRaiserError::InvalidContributorAccount => ProgramError::Custom(1020),
+            FundRaiserError::InvalidMaker => ProgramError::Custom(1021),
+            FundRaiserError::InvalidMilestoneSchedule => ProgramError::Custom(1022),
+            FundRaiserError::InvalidMilestones => ProgramError::Custom(1023),
+            FundRaiserError::TrancheLocked => ProgramError::Custom(1024),
+            FundRaiserError::MilestoneCampaign => ProgramError::Custom(1025),
+            FundRaiserError::InvalidGovernance => ProgramError::Custom(1026),
+            FundRaiserError::GovernanceDisabled => ProgramError::Custom(1027),
+            FundRaiserError::AlreadyVoted => ProgramError::Custom(1028),
+            FundRaiserError::ReleaseNotApproved => ProgramError::Custom(1029),
+            FundRaiserError::ReleaseRejected => ProgramError::Custom(1030),
+            FundRaiserError::InvalidReceiptMint => ProgramError::Custom(1031),
+            FundRaiserError::NotAllowlisted => ProgramError::Custom(1032),
+            FundRaiserError::AllocationExceeded => ProgramError::Custom(1033),
+            FundRaiserError::InvalidConfig => ProgramError::Custom(1034),
+            FundRaiserError::InvalidFee => ProgramError::Custom(1035),
+            FundRaiserError::InvalidFeeRecipient => ProgramError::Custom(1036),
+            FundRaiserError::InvalidAccountVersion => ProgramError::Custom(1037),
+            FundRaiserError::ParametersLocked => ProgramError::Custom(1038),
+            FundRaiserError::DeadlineShortened => ProgramError::Custom(1039),
+            FundRaiserError::InvalidStretchGoals => ProgramError::Custom(1040),
+            FundRaiserError::ReceiptCampaign => ProgramError::Custom(1041),
+            FundRaiserError::InvalidTokenAccount => ProgramError::Custom(1042),
+            FundRaiserError::WithdrawalsDisabled => ProgramError::Custom(1043),
+            FundRaiserError::WithdrawalCooldown => ProgramError::Custom(1044),
+            FundRaiserError::InvalidWithdrawTerms => ProgramError::Custom(1045),
+            FundRaiserError::WithdrawalTooBig => ProgramError::Custom(1046),
+            FundRaiserError::NotUpgradeAuthority => ProgramError::Custom(1047),
+            FundRaiserError::VotingClosed => ProgramError::Custom(1048),
+            FundRaiserError::TransferHookMint => ProgramError::Custom(1049),
+            FundRaiserError::RefundsOutstanding => ProgramError::Custom(1050),
+            FundRaiserError::LegacyAmountExceeded => ProgramError::Custom(1051),
+            FundRaiserError::LegacyVaultDrained => ProgramError::Custom(1052),
+            FundRaiserError::InvalidSettlement => ProgramError::Custom(1053),
+            FundRaiserError::InvalidProgram => ProgramError::Custom(1054),
             
              }
     }
@@ -42,6 +136,53 @@ impl FundRaiserError {
             FundRaiserError::FundraiserNotEnded => "The fundraiser has not ended yet",
             FundRaiserError::FundraiserEnded => "The fundraiser has ended",
             FundRaiserError::InvalidAmount => "Invalid total amount. i should be bigger than 3",
+            FundRaiserError::Unauthorized => "The signer is not the maker of this fundraiser",
+            FundRaiserError::FundraiserCancelled => "The fundraiser has been cancelled",
+            FundRaiserError::AlreadyRefunded => "The contribution has already been refunded",
+            FundRaiserError::FundraiserNotStarted => "The fundraiser has not started yet",
+            FundRaiserError::InvalidSchedule => "The start time must not be in the past and must be before the end time",
+            FundRaiserError::InvalidContributionLimits => "The minimum contribution must be positive and not exceed the maximum per contributor",
+            FundRaiserError::InvalidHardCap => "The hard cap must not be below the amount to raise",
+            FundRaiserError::HardCapReached => "The contribution would exceed the hard cap",
+            FundRaiserError::InvalidTokenProgram => "The token program must be SPL Token or Token-2022 and own the mint and token accounts",
+            FundRaiserError::InvalidVault => "The vault is not the fundraiser's vault",
+            FundRaiserError::InvalidFundraiser => "The fundraiser account is not a fundraiser owned by this program",
+            FundRaiserError::InvalidMint => "The mint does not match the fundraiser's mint",
+            FundRaiserError::InvalidContributorAccount => "The contributor account is not the contributor's record for this fundraiser",
+            FundRaiserError::InvalidMaker => "The maker does not match the fundraiser's maker",
+            FundRaiserError::InvalidMilestoneSchedule => "Tranches must sum to 100% and unlock in order after the campaign ends",
+            FundRaiserError::InvalidMilestones => "The milestones account is not the fundraiser's release schedule",
+            FundRaiserError::TrancheLocked => "The next tranche has not unlocked yet",
+            FundRaiserError::MilestoneCampaign => "Milestone campaigns release funds through ClaimTranche",
+            FundRaiserError::InvalidGovernance => "Quorum and approval threshold must be between 1 and 10000 basis points",
+            FundRaiserError::GovernanceDisabled => "The fundraiser does not put releases to a vote",
+            FundRaiserError::AlreadyVoted => "The contributor already voted on this release",
+            FundRaiserError::ReleaseNotApproved => "Contributors have not approved the release yet",
+            FundRaiserError::ReleaseRejected => "Contributors rejected the release",
+            FundRaiserError::InvalidReceiptMint => "The receipt mint is not the fundraiser's receipt mint",
+            FundRaiserError::NotAllowlisted => "The Merkle proof does not match the fundraiser's allowlist",
+            FundRaiserError::AllocationExceeded => "The contribution would exceed the contributor's allocation",
+            FundRaiserError::InvalidConfig => "The config account is not the program's config",
+            FundRaiserError::InvalidFee => "The platform fee exceeds the maximum allowed",
+            FundRaiserError::InvalidFeeRecipient => "The fee account does not belong to the configured fee recipient",
+            FundRaiserError::InvalidAccountVersion => "The account uses an outdated or unknown layout; run Migrate first",
+            FundRaiserError::ParametersLocked => "Only the deadline can change once contributions have been made",
+            FundRaiserError::DeadlineShortened => "The deadline can only be extended once contributions have been made",
+            FundRaiserError::InvalidStretchGoals => "Stretch goals must rise above the target in order and stay within the hard cap",
+            FundRaiserError::ReceiptCampaign => "Receipt campaigns are refunded by each contributor burning their receipts",
+            FundRaiserError::InvalidTokenAccount => "The token account does not hold the fundraiser's mint for its expected owner",
+            FundRaiserError::WithdrawalsDisabled => "The fundraiser does not allow withdrawals",
+            FundRaiserError::WithdrawalCooldown => "The contribution is still in its withdrawal cooldown",
+            FundRaiserError::InvalidWithdrawTerms => "The withdrawal cooldown must not be negative and the penalty must stay below 10000 basis points",
+            FundRaiserError::WithdrawalTooBig => "The withdrawal exceeds the contribution",
+            FundRaiserError::NotUpgradeAuthority => "Only the program's upgrade authority can create the config",
+            FundRaiserError::VotingClosed => "Voting on this release has closed",
+            FundRaiserError::TransferHookMint => "Transfer-hook mints are refunded by each contributor calling Refund",
+            FundRaiserError::RefundsOutstanding => "Contributions are still waiting to be refunded",
+            FundRaiserError::LegacyAmountExceeded => "The unversioned record claims more than its fundraiser has left unrefunded",
+            FundRaiserError::LegacyVaultDrained => "The unversioned fundraiser already paid out its vault",
+            FundRaiserError::InvalidSettlement => "The settlement account is not the fundraiser's settlement record",
+            FundRaiserError::InvalidProgram => "A program account is not the program the instruction expects",
             
 
              }
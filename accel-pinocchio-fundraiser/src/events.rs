@@ -0,0 +1,227 @@
+//! Binary events for indexers. Each event is logged with `sol_log_data` as a single buffer
+//! `kind (u8) | payload`, where the payload is the little-endian `#[repr(C)]` struct below, so a
+//! `Program data: <base64>` log line decodes with [`Event::decode`].
+
+use bytemuck::{Pod, Zeroable};
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EventKind {
+    Initialized = 0,
+    Contributed = 1,
+    Checked = 2,
+    Refunded = 3,
+    Cancelled = 4,
+    Withdrawn = 5,
+    Settled = 6,
+    Updated = 7,
+    TrancheClaimed = 8,
+    Voted = 9,
+    Closed = 10,
+}
+
+impl TryFrom<u8> for EventKind {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(EventKind::Initialized),
+            1 => Ok(EventKind::Contributed),
+            2 => Ok(EventKind::Checked),
+            3 => Ok(EventKind::Refunded),
+            4 => Ok(EventKind::Cancelled),
+            5 => Ok(EventKind::Withdrawn),
+            6 => Ok(EventKind::Settled),
+            7 => Ok(EventKind::Updated),
+            8 => Ok(EventKind::TrancheClaimed),
+            9 => Ok(EventKind::Voted),
+            10 => Ok(EventKind::Closed),
+            _ => Err(()),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Initialized {
+    pub fundraiser: [u8; 32],
+    pub maker: [u8; 32],
+    /// All zeros for native SOL campaigns.
+    pub mint_to_raise: [u8; 32],
+    pub amount_to_raise: u64,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub campaign_id: u64,
+    pub hard_cap: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Contributed {
+    pub fundraiser: [u8; 32],
+    pub contributor: [u8; 32],
+    /// Amount credited, net of transfer fees and hard cap clamping.
+    pub amount: u64,
+    pub contributor_total: u64,
+    pub current_amount: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Checked {
+    pub fundraiser: [u8; 32],
+    pub maker: [u8; 32],
+    pub maker_amount: u64,
+    pub fee_amount: u64,
+    /// `FundRaiser::tier_for` the amount raised: 1 for the base goal, one more per stretch goal.
+    pub tier: u8,
+    pub _padding: [u8; 7],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Refunded {
+    pub fundraiser: [u8; 32],
+    pub contributor: [u8; 32],
+    /// The contributor's recorded amount, which is what `current_amount` drops by.
+    pub amount: u64,
+    /// What actually left the vault, lower than `amount` when refunds are pro rata.
+    pub payout: u64,
+    pub current_amount: u64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Cancelled {
+    pub fundraiser: [u8; 32],
+    pub maker: [u8; 32],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Withdrawn {
+    pub fundraiser: [u8; 32],
+    pub contributor: [u8; 32],
+    /// What `current_amount` and the contributor's total drop by.
+    pub amount: u64,
+    /// Part of `amount` kept in the vault instead of paid to the contributor.
+    pub penalty: u64,
+    pub contributor_total: u64,
+    pub current_amount: u64,
+}
+
+/// A milestone campaign settling on its first `ClaimTranche`; the tranches then follow.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Settled {
+    pub fundraiser: [u8; 32],
+    pub maker: [u8; 32],
+    pub raised_amount: u64,
+    /// `FundRaiser::tier_for` the amount raised, as in `Checked`.
+    pub tier: u8,
+    pub _padding: [u8; 7],
+}
+
+/// The maker changed the campaign's terms; carries the terms now in force.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Updated {
+    pub fundraiser: [u8; 32],
+    pub maker: [u8; 32],
+    pub amount_to_raise: u64,
+    pub end_ts: i64,
+    pub min_contribution: u64,
+    pub max_per_contributor: u64,
+    /// 0 for no hard cap.
+    pub hard_cap: u64,
+    /// `ContributionCapKind` of `max_per_contributor`.
+    pub cap_kind: u8,
+    pub _padding: [u8; 7],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct TrancheClaimed {
+    pub fundraiser: [u8; 32],
+    pub maker: [u8; 32],
+    /// What left the vault for this tranche, platform fee included.
+    pub amount: u64,
+    pub fee_amount: u64,
+    /// Left in the vault for the tranches still to come.
+    pub remaining: u64,
+    pub tranche_index: u8,
+    pub _padding: [u8; 7],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Voted {
+    pub fundraiser: [u8; 32],
+    pub contributor: [u8; 32],
+    /// The contributor's recorded amount.
+    pub weight: u64,
+    /// Tallies for the release after this vote.
+    pub votes_for: u64,
+    pub votes_against: u64,
+    /// Index of the release voted on, see `FundRaiser::vote_round`.
+    pub vote_round: u8,
+    /// 1 to approve, 0 to reject.
+    pub approve: u8,
+    pub _padding: [u8; 6],
+}
+
+/// A failed campaign torn down by its maker once every contribution was refunded.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+pub struct Closed {
+    pub fundraiser: [u8; 32],
+    pub maker: [u8; 32],
+    /// Forfeited withdrawal penalties left in the vault, paid to the maker.
+    pub amount: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Initialized(Initialized),
+    Contributed(Contributed),
+    Checked(Checked),
+    Refunded(Refunded),
+    Cancelled(Cancelled),
+    Withdrawn(Withdrawn),
+    Settled(Settled),
+    Updated(Updated),
+    TrancheClaimed(TrancheClaimed),
+    Voted(Voted),
+    Closed(Closed),
+}
+
+impl Event {
+    /// Decodes one `sol_log_data` buffer; `None` if it is not a well-formed fundraiser event.
+    pub fn decode(data: &[u8]) -> Option<Event> {
+        let (kind, payload) = data.split_first()?;
+        Some(match EventKind::try_from(*kind).ok()? {
+            EventKind::Initialized => Event::Initialized(read(payload)?),
+            EventKind::Contributed => Event::Contributed(read(payload)?),
+            EventKind::Checked => Event::Checked(read(payload)?),
+            EventKind::Refunded => Event::Refunded(read(payload)?),
+            EventKind::Cancelled => Event::Cancelled(read(payload)?),
+            EventKind::Withdrawn => Event::Withdrawn(read(payload)?),
+            EventKind::Settled => Event::Settled(read(payload)?),
+            EventKind::Updated => Event::Updated(read(payload)?),
+            EventKind::TrancheClaimed => Event::TrancheClaimed(read(payload)?),
+            EventKind::Voted => Event::Voted(read(payload)?),
+            EventKind::Closed => Event::Closed(read(payload)?),
+        })
+    }
+}
+
+fn read<T: Pod>(payload: &[u8]) -> Option<T> {
+    bytemuck::try_pod_read_unaligned(payload).ok()
+}
+
+pub fn emit<T: Pod>(kind: EventKind, event: &T) {
+    let mut data = Vec::with_capacity(1 + core::mem::size_of::<T>());
+    data.push(kind as u8);
+    data.extend_from_slice(bytemuck::bytes_of(event));
+    pinocchio::log::sol_log_data(&[&data]);
+}
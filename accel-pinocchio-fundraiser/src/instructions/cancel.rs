@@ -0,0 +1,43 @@
+use pinocchio::{
+    account_info::AccountInfo, ProgramResult
+};
+use crate::{error::FundRaiserError, events::{emit, Cancelled, EventKind}, state::fundraiser::{FundRaiser, FundRaiserStatus}, validation::check_fundraiser_account};
+
+pub fn process_cancel_instruction(
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing Cancel instruction");
+
+    let [
+        maker,
+        fundraiser,
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !maker.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_fundraiser_account(fundraiser)?;
+
+    let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+
+    // Only the maker that created the campaign can cancel it
+    if fundraiser_state.maker() != *maker.key() {
+        return Err(FundRaiserError::Unauthorized.into());
+    }
+
+    if fundraiser_state.is_cancelled() {
+        return Err(FundRaiserError::FundraiserCancelled.into());
+    }
+
+    // From here on contributors can refund regardless of elapsed time or vault balance
+    fundraiser_state.set_status(FundRaiserStatus::Cancelled);
+
+    emit(EventKind::Cancelled, &Cancelled {
+        fundraiser: *fundraiser.key(),
+        maker: *maker.key(),
+    });
+
+    Ok(())
+}
@@ -1,65 +1,159 @@
 use pinocchio::{
-    account_info::AccountInfo, instruction::{Seed, Signer}, msg, ProgramResult
+    account_info::AccountInfo, instruction::{Seed, Signer}, sysvars::{clock::Clock, Sysvar}, ProgramResult
 };
-use pinocchio_token::state::TokenAccount;
-use crate::{error::FundRaiserError, state::fundraiser::FundRaiser};
+use crate::{error::FundRaiserError, events::{emit, Checked, EventKind}, state::{config::Config, fundraiser::{FundRaiser, FundRaiserPhase}}, token::{close_account, mint_decimals, token_account_amount, transfer_checked}, utils::{close_program_account, create_settlement, native_vault_balance, transfer_lamports}, validation::{check_config_account, check_fee_recipient, check_fundraiser_account, check_mint, check_program, check_release_approved, check_vault}};
 
 pub fn process_checker_instruction(
     accounts: &[AccountInfo],
     _data: &[u8],
 ) -> ProgramResult {
 
-    msg!("Processing Checker instruction");
+    trace!("Processing Checker instruction");
 
     let [
         maker,
-        _mint_to_raise,
+        mint_to_raise,
         fundraiser,
         vault,
         maker_ata,
-        _token_program,
-        _system_program,
-         _associated_token_program,
-       // _rent_sysvar @ ..
+        token_program,
+        system_program,
+        associated_token_program,
+        config,
+        fee_recipient,
+        // the fee recipient's ATA for `mint_to_raise` (unused for native SOL campaigns)
+        fee_recipient_ata,
+        // with FLAG_STRETCH_GOALS: `[b"settlement", fundraiser]`; then transfer-hook extra accounts,
+        // forwarded to the token program
+        remaining @ ..
     ] = accounts else {
         return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
     };
 
+    if !maker.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+    check_program(associated_token_program, &pinocchio_associated_token_account::ID)?;
+    check_fundraiser_account(fundraiser)?;
+    let config_initialized = check_config_account(config)?;
+
+    // Read and validate state within a limited scope to avoid holding borrows over CPI
+    let (native, vault_amount, fee_amount, tier, settlement, hook_accounts, campaign_seed, bump) = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+
+        if fundraiser_state.maker() != *maker.key() {
+            return Err(FundRaiserError::Unauthorized.into());
+        }
+        if fundraiser_state.has_milestones() {
+            return Err(FundRaiserError::MilestoneCampaign.into());
+        }
+        check_mint(fundraiser_state, mint_to_raise, token_program)?;
+        check_vault(fundraiser_state, fundraiser, vault, token_program)?;
+        let (settlement, hook_accounts) = if fundraiser_state.has_stretch_goals() {
+            let [settlement, hook_accounts @ ..] = remaining else {
+                return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+            };
+            (Some(settlement), hook_accounts)
+        } else {
+            (None, remaining)
+        };
+
+        if fundraiser_state.is_cancelled() {
+            return Err(FundRaiserError::FundraiserCancelled.into());
+        }
+
+        // Wait for the deadline, unless the campaign closed early on reaching its target
+        let now = Clock::get()?.unix_timestamp;
+        if fundraiser_state.phase(now) != FundRaiserPhase::Ended {
+            return Err(FundRaiserError::FundraiserNotEnded.into());
+        }
+        check_release_approved(fundraiser_state, now)?;
+
+        if !fundraiser_state.target_met() {
+            return Err(FundRaiserError::TargetNotMet.into());
+        }
+        let vault_amount = if fundraiser_state.is_native() {
+            native_vault_balance(vault)?
+        } else {
+            token_account_amount(vault, token_program)?
+        };
+
+        // Settle the campaign at the tier the raise reached
+        let tier = fundraiser_state.tier_for(fundraiser_state.raised_amount());
+
+        // The platform takes its cut before the maker is paid
+        let fee_amount = if config_initialized {
+            let config_state = Config::from_account_info(config)?;
+            check_fee_recipient(config_state, fundraiser_state, fee_recipient, fee_recipient_ata, token_program)?;
+            config_state.fee_amount(vault_amount)
+        } else {
+            0
+        };
+
+        (fundraiser_state.is_native(), vault_amount, fee_amount, tier, settlement, hook_accounts, fundraiser_state.campaign_seed(), fundraiser_state.bump)
+    };
+    let maker_amount = vault_amount - fee_amount;
+    emit(EventKind::Checked, &Checked {
+        fundraiser: *fundraiser.key(),
+        maker: *maker.key(),
+        maker_amount,
+        fee_amount,
+        tier,
+        _padding: [0; 7],
+    });
+    // The fundraiser is closed below: the tier is kept where it can still be read
+    if let Some(settlement) = settlement {
+        create_settlement(maker, fundraiser, settlement, vault_amount, tier)?;
+    }
+
+    if native {
+        if fee_amount > 0 {
+            transfer_lamports(vault, fee_recipient, fee_amount)?;
+        }
+        // The rest of the vault, rent reserve included, goes to the maker
+        close_program_account(vault, maker)?;
+        close_program_account(fundraiser, maker)?;
+        return Ok(());
+    }
+
     // init_if_needed for maker_ata: create ATA for (maker, mint) if missing
     if maker_ata.lamports() == 0 || maker_ata.data_is_empty() {
         pinocchio_associated_token_account::instructions::Create {
             funding_account: maker,
             account: maker_ata,
             wallet: maker,
-            mint: _mint_to_raise,
-            token_program: _token_program,
-            system_program: _system_program,
+            mint: mint_to_raise,
+            token_program,
+            system_program,
         }
         .invoke()?;
     }
 
- let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
-
-  
-let vault_acc = TokenAccount::from_account_info(vault)?;
-          let vault_amount = vault_acc.amount();
-          if vault_amount < fundraiser_state.amount_to_raise() {
-            return Err(FundRaiserError::TargetNotMet.into());
-            }
-
- // Transfer the funds from the vault to the maker
-    let bump_arr = [fundraiser_state.bump.to_le()];
-    let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(&bump_arr)];
+    // Transfer the funds from the vault to the maker
+    let bump_arr = [bump];
+    let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(campaign_seed.as_slice()), Seed::from(&bump_arr)];
     let signer = Signer::from(&seed);
 
-    pinocchio_token::instructions::Transfer {
-        from: vault,
-        to: maker_ata,
-        authority: fundraiser,
-        amount: vault_acc.amount(),
+    let decimals = mint_decimals(mint_to_raise, token_program)?;
+    if fee_amount > 0 {
+        if fee_recipient_ata.lamports() == 0 || fee_recipient_ata.data_is_empty() {
+            pinocchio_associated_token_account::instructions::Create {
+                funding_account: maker,
+                account: fee_recipient_ata,
+                wallet: fee_recipient,
+                mint: mint_to_raise,
+                token_program,
+                system_program,
+            }
+            .invoke()?;
+        }
+        transfer_checked(token_program, vault, mint_to_raise, fee_recipient_ata, fundraiser, fee_amount, decimals, hook_accounts, &[signer.clone()])?;
     }
-    .invoke_signed(&[signer])?;
+    transfer_checked(token_program, vault, mint_to_raise, maker_ata, fundraiser, maker_amount, decimals, hook_accounts, &[signer.clone()])?;
+
+    // Tear the campaign down: close the vault and the fundraiser state back to the maker
+    close_account(token_program, vault, mint_to_raise, maker, fundraiser, &[signer])?;
 
+    close_program_account(fundraiser, maker)?;
 
-  Ok(())
+    Ok(())
 }
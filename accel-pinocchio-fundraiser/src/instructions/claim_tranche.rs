@@ -0,0 +1,222 @@
+use pinocchio::{
+    account_info::AccountInfo, instruction::{Seed, Signer}, sysvars::{clock::Clock, Sysvar}, ProgramResult
+};
+use crate::{
+    constants::VOTING_PERIOD,
+    error::FundRaiserError,
+    events::{emit, EventKind, Settled, TrancheClaimed},
+    state::{config::Config, fundraiser::{FundRaiser, FundRaiserPhase, FundRaiserStatus}, milestones::Milestones},
+    token::{close_account, mint_decimals, token_account_amount, transfer_checked},
+    utils::{close_program_account, create_settlement, native_vault_balance, transfer_lamports},
+    validation::{check_config_account, check_fee_recipient, check_fundraiser_account, check_milestones_account, check_mint, check_program, check_release_approved, check_vault},
+};
+
+pub fn process_claim_tranche_instruction(
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing ClaimTranche instruction");
+
+    let [
+        maker,
+        mint_to_raise,
+        fundraiser,
+        milestones,
+        vault,
+        maker_ata,
+        token_program,
+        system_program,
+        associated_token_program,
+        config,
+        fee_recipient,
+        // the fee recipient's ATA for `mint_to_raise` (unused for native SOL campaigns)
+        fee_recipient_ata,
+        // with FLAG_STRETCH_GOALS: `[b"settlement", fundraiser]`, created by the first claim; then
+        // transfer-hook extra accounts, forwarded to the token program
+        remaining @ ..
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !maker.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+    check_program(associated_token_program, &pinocchio_associated_token_account::ID)?;
+    check_fundraiser_account(fundraiser)?;
+    check_milestones_account(fundraiser, milestones)?;
+    let config_initialized = check_config_account(config)?;
+
+    // Read and validate state within a limited scope to avoid holding borrows over CPI
+    let (native, index, amount, fee_amount, vault_remaining, last, settled, settlement, hook_accounts, campaign_seed, bump) = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+
+        if fundraiser_state.maker() != *maker.key() {
+            return Err(FundRaiserError::Unauthorized.into());
+        }
+        if !fundraiser_state.has_milestones() {
+            return Err(FundRaiserError::InvalidMilestones.into());
+        }
+        check_mint(fundraiser_state, mint_to_raise, token_program)?;
+        check_vault(fundraiser_state, fundraiser, vault, token_program)?;
+        let (settlement, hook_accounts) = if fundraiser_state.has_stretch_goals() {
+            let [settlement, hook_accounts @ ..] = remaining else {
+                return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+            };
+            (Some(settlement), hook_accounts)
+        } else {
+            (None, remaining)
+        };
+
+        if fundraiser_state.is_cancelled() {
+            return Err(FundRaiserError::FundraiserCancelled.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        if fundraiser_state.phase(now) != FundRaiserPhase::Ended {
+            return Err(FundRaiserError::FundraiserNotEnded.into());
+        }
+        check_release_approved(fundraiser_state, now)?;
+
+        let vault_amount = if fundraiser_state.is_native() {
+            native_vault_balance(vault)?
+        } else {
+            token_account_amount(vault, token_program)?
+        };
+
+        let milestones_state = Milestones::from_account_info(milestones)?;
+
+        // The first claim settles the campaign: the target must be met, the raise and its tier are fixed
+        let settled = if milestones_state.claimed_count() == 0 {
+            if !fundraiser_state.target_met() {
+                return Err(FundRaiserError::TargetNotMet.into());
+            }
+            let tier = fundraiser_state.tier_for(fundraiser_state.raised_amount());
+            milestones_state.set_raised_amount(&vault_amount);
+            fundraiser_state.set_status(FundRaiserStatus::Releasing);
+            emit(EventKind::Settled, &Settled {
+                fundraiser: *fundraiser.key(),
+                maker: *maker.key(),
+                raised_amount: vault_amount,
+                tier,
+                _padding: [0; 7],
+            });
+            Some((vault_amount, tier))
+        } else {
+            None
+        };
+
+        let index = milestones_state.claimed_count();
+        let tranche = milestones_state.tranche(index).ok_or(FundRaiserError::InvalidMilestones)?;
+        if now < tranche.unlock_ts() {
+            return Err(FundRaiserError::TrancheLocked.into());
+        }
+
+        // The last tranche sweeps the vault so rounding never strands dust in it
+        let last = index + 1 == milestones_state.tranche_count();
+        let amount = if last { vault_amount } else { milestones_state.tranche_amount(index)? };
+
+        let released = milestones_state.released_amount()
+            .checked_add(amount)
+            .ok_or(pinocchio::program_error::ProgramError::ArithmeticOverflow)?;
+        milestones_state.set_released_amount(&released);
+        milestones_state.set_claimed_count(index + 1);
+        // The following tranche goes to a fresh vote, open until a voting period after it unlocks
+        fundraiser_state.next_vote_round();
+        if let Some(next) = milestones_state.tranche(index + 1) {
+            fundraiser_state.set_vote_end_ts(&now.max(next.unlock_ts()).saturating_add(VOTING_PERIOD));
+        }
+
+        // The platform takes its cut of every tranche before the maker is paid
+        let fee_amount = if config_initialized {
+            let config_state = Config::from_account_info(config)?;
+            check_fee_recipient(config_state, fundraiser_state, fee_recipient, fee_recipient_ata, token_program)?;
+            config_state.fee_amount(amount)
+        } else {
+            0
+        };
+
+        (
+            fundraiser_state.is_native(),
+            index,
+            amount,
+            fee_amount,
+            vault_amount.saturating_sub(amount),
+            last,
+            settled,
+            settlement,
+            hook_accounts,
+            fundraiser_state.campaign_seed(),
+            fundraiser_state.bump,
+        )
+    };
+    // The tier outlives the fundraiser, which the last claim closes
+    if let (Some((raised_amount, tier)), Some(settlement)) = (settled, settlement) {
+        create_settlement(maker, fundraiser, settlement, raised_amount, tier)?;
+    }
+    emit(EventKind::TrancheClaimed, &TrancheClaimed {
+        fundraiser: *fundraiser.key(),
+        maker: *maker.key(),
+        amount,
+        fee_amount,
+        remaining: vault_remaining,
+        tranche_index: index,
+        _padding: [0; 7],
+    });
+
+    if native {
+        if fee_amount > 0 {
+            transfer_lamports(vault, fee_recipient, fee_amount)?;
+        }
+        if last {
+            // The whole vault, rent reserve included, goes to the maker
+            close_program_account(vault, maker)?;
+        } else {
+            transfer_lamports(vault, maker, amount - fee_amount)?;
+        }
+    } else {
+        // init_if_needed for maker_ata: create ATA for (maker, mint) if missing
+        if maker_ata.lamports() == 0 || maker_ata.data_is_empty() {
+            pinocchio_associated_token_account::instructions::Create {
+                funding_account: maker,
+                account: maker_ata,
+                wallet: maker,
+                mint: mint_to_raise,
+                token_program,
+                system_program,
+            }
+            .invoke()?;
+        }
+        let bump_arr = [bump];
+        let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(campaign_seed.as_slice()), Seed::from(&bump_arr)];
+        let signer = Signer::from(&seed);
+
+        let decimals = mint_decimals(mint_to_raise, token_program)?;
+        if fee_amount > 0 {
+            if fee_recipient_ata.lamports() == 0 || fee_recipient_ata.data_is_empty() {
+                pinocchio_associated_token_account::instructions::Create {
+                    funding_account: maker,
+                    account: fee_recipient_ata,
+                    wallet: fee_recipient,
+                    mint: mint_to_raise,
+                    token_program,
+                    system_program,
+                }
+                .invoke()?;
+            }
+            transfer_checked(token_program, vault, mint_to_raise, fee_recipient_ata, fundraiser, fee_amount, decimals, hook_accounts, &[signer.clone()])?;
+        }
+        transfer_checked(token_program, vault, mint_to_raise, maker_ata, fundraiser, amount - fee_amount, decimals, hook_accounts, &[signer.clone()])?;
+
+        if last {
+            close_account(token_program, vault, mint_to_raise, maker, fundraiser, &[signer])?;
+        }
+    }
+
+    // Once everything is released the schedule and the campaign are torn down
+    if last {
+        close_program_account(milestones, maker)?;
+        close_program_account(fundraiser, maker)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,114 @@
+use pinocchio::{
+    account_info::AccountInfo, instruction::{Seed, Signer}, sysvars::{clock::Clock, Sysvar}, ProgramResult
+};
+use crate::{
+    error::FundRaiserError,
+    events::{emit, Closed, EventKind},
+    state::fundraiser::FundRaiser,
+    token::{close_account, mint_decimals, token_account_amount, transfer_checked},
+    utils::{close_program_account, native_vault_balance},
+    validation::{check_fundraiser_account, check_milestones_account, check_mint, check_program, check_refundable, check_vault},
+};
+
+pub fn process_close_instruction(
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing Close instruction");
+
+    let [
+        maker,
+        mint_to_raise,
+        fundraiser,
+        vault,
+        maker_ata,
+        token_program,
+        system_program,
+        associated_token_program,
+        // with FLAG_MILESTONES: milestones; then transfer-hook extra accounts, forwarded to the token program
+        remaining @ ..
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !maker.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+    check_program(associated_token_program, &pinocchio_associated_token_account::ID)?;
+    check_fundraiser_account(fundraiser)?;
+
+    // Read and validate state within a limited scope to avoid holding borrows over CPI
+    let (native, milestones, hook_accounts, vault_amount, campaign_seed, bump) = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+
+        if fundraiser_state.maker() != *maker.key() {
+            return Err(FundRaiserError::Unauthorized.into());
+        }
+        check_mint(fundraiser_state, mint_to_raise, token_program)?;
+        check_vault(fundraiser_state, fundraiser, vault, token_program)?;
+        let (milestones, hook_accounts) = if fundraiser_state.has_milestones() {
+            let [milestones, hook_accounts @ ..] = remaining else {
+                return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+            };
+            check_milestones_account(fundraiser, milestones)?;
+            (Some(milestones), hook_accounts)
+        } else {
+            (None, remaining)
+        };
+
+        // Only a campaign that failed, and only once every contribution has gone back to its backer
+        check_refundable(fundraiser_state, Clock::get()?.unix_timestamp)?;
+        if fundraiser_state.current_amount() != 0 {
+            return Err(FundRaiserError::RefundsOutstanding.into());
+        }
+
+        let vault_amount = if fundraiser_state.is_native() {
+            native_vault_balance(vault)?
+        } else {
+            token_account_amount(vault, token_program)?
+        };
+
+        (fundraiser_state.is_native(), milestones, hook_accounts, vault_amount, fundraiser_state.campaign_seed(), fundraiser_state.bump)
+    };
+
+    // What is left is forfeited withdrawal penalties nobody remained to share
+    emit(EventKind::Closed, &Closed {
+        fundraiser: *fundraiser.key(),
+        maker: *maker.key(),
+        amount: vault_amount,
+    });
+
+    if native {
+        // The vault, rent reserve included, goes to the maker
+        close_program_account(vault, maker)?;
+    } else {
+        let bump_arr = [bump];
+        let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(campaign_seed.as_slice()), Seed::from(&bump_arr)];
+        let signer = Signer::from(&seed);
+
+        if vault_amount > 0 {
+            // init_if_needed for maker_ata: create ATA for (maker, mint) if missing
+            if maker_ata.lamports() == 0 || maker_ata.data_is_empty() {
+                pinocchio_associated_token_account::instructions::Create {
+                    funding_account: maker,
+                    account: maker_ata,
+                    wallet: maker,
+                    mint: mint_to_raise,
+                    token_program,
+                    system_program,
+                }
+                .invoke()?;
+            }
+            let decimals = mint_decimals(mint_to_raise, token_program)?;
+            transfer_checked(token_program, vault, mint_to_raise, maker_ata, fundraiser, vault_amount, decimals, hook_accounts, &[signer.clone()])?;
+        }
+        close_account(token_program, vault, mint_to_raise, maker, fundraiser, &[signer])?;
+    }
+
+    if let Some(milestones) = milestones {
+        close_program_account(milestones, maker)?;
+    }
+    close_program_account(fundraiser, maker)?;
+
+    Ok(())
+}
@@ -1,16 +1,19 @@
 use pinocchio::{
-    account_info::AccountInfo, msg, sysvars::{clock::Clock, Sysvar}, ProgramResult
+    account_info::AccountInfo, instruction::{Seed, Signer}, sysvars::{clock::Clock, Sysvar}, ProgramResult
 };
-use crate::{error::FundRaiserError, state::fundraiser::FundRaiser,constants};
+use crate::{error::FundRaiserError, state::fundraiser::{FundRaiser, FundRaiserPhase}};
 use crate::state::contributor::Contributor;
-use pinocchio_token::state::Mint;
+use crate::events::{emit, Contributed, EventKind};
+use crate::merkle::{allowlist_leaf, verify_proof};
+use crate::token::{check_token_account, mint_decimals, mint_to, token_account_amount, transfer_checked};
+use crate::validation::{check_contributor_account, check_fundraiser_account, check_mint, check_program, check_receipt_mint, check_vault};
 pub fn process_contribute_instruction(
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
 
 
-    msg!("Processing Contribute instruction");
+    trace!("Processing Contribute instruction");
 
     let [
         contributor,
@@ -19,30 +22,39 @@ pub fn process_contribute_instruction(
         contributor_account,
         contributor_ata,
         vault,
-        _token_program,
-        _system_program,
-       // _rent_sysvar @ ..
+        token_program,
+        system_program,
+        // with FLAG_RECEIPT: receipt_mint, contributor_receipt; then transfer-hook extra accounts,
+        // forwarded to the token program
+        remaining @ ..
     ] = accounts else {
         return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
     };
 
-    // Accept provided contributor account (tests pre-create a program-owned account)
-    msg!("contribute: post-destructure");
-
-    // Derive canonical PDA: [b"contributor", fundraiser, contributor]
-    let seeds_raw = [
-        b"contributor".as_ref(),
-        fundraiser.key().as_ref(),
-        contributor.key().as_ref(),
-    ];
-    msg!("contribute: deriving PDA");
-    let (expected_pda, bump) = pinocchio::pubkey::find_program_address(&seeds_raw, &crate::ID);
-    msg!("contribute: derived contributor PDA");
-    // Create if needed (init_if_needed) only when empty and the key matches the canonical PDA
-    if (contributor_account.lamports() == 0 || contributor_account.data_is_empty())
-        && expected_pda == *contributor_account.key()
-    {
-        msg!("contribute: creating contributor PDA");
+    check_program(system_program, &pinocchio_system::ID)?;
+    check_fundraiser_account(fundraiser)?;
+    let (receipt_accounts, hook_accounts) = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+        check_mint(fundraiser_state, mint_to_raise, token_program)?;
+        check_vault(fundraiser_state, fundraiser, vault, token_program)?;
+        if fundraiser_state.has_receipt() {
+            let [receipt_mint, contributor_receipt, hook_accounts @ ..] = remaining else {
+                return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+            };
+            check_receipt_mint(fundraiser_state, fundraiser, receipt_mint, token_program)?;
+            // Receipts only go to, and are only taken from, the contributor's own receipt account
+            check_token_account(contributor_receipt, contributor.key(), receipt_mint.key(), token_program)?;
+            (Some((receipt_mint, contributor_receipt)), hook_accounts)
+        } else {
+            (None, remaining)
+        }
+    };
+
+    // Canonical PDA: [b"contributor", fundraiser, contributor]; the bump is only searched for when
+    // the record is created, existing records carry it
+    let bump = check_contributor_account(contributor_account, fundraiser, contributor)?;
+    // Create if needed (init_if_needed)
+    if contributor_account.data_is_empty() {
         let bump_arr = [bump];
         let seeds = [
             pinocchio::instruction::Seed::from(b"contributor"),
@@ -61,40 +73,42 @@ pub fn process_contribute_instruction(
         .invoke_signed(&[signer])?;
 
         let st = Contributor::from_account_info(contributor_account)?;
+        st.init_header();
         st.set_amount(&0);
+        st.bump = bump;
     }
-    msg!("contribute: init_if_needed handled");
 
-    ///
     let contributor_state = Contributor::from_account_info(contributor_account)?;
     let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
-    msg!("contribute: loaded accounts");
+
+    // A cancelled fundraiser no longer accepts contributions
+    if fundraiser_state.is_cancelled() {
+        return Err(FundRaiserError::FundraiserCancelled.into());
+    }
     
-    let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
-
- // Check if the amount to contribute meets the minimum amount required
-    let mint = Mint::from_account_info(mint_to_raise)?;
-    msg!("contribute: loaded mint");
-            let decimals = mint.decimals();
-            let min_unit = 10_u64.pow(decimals as u32);
-            if amount < min_unit {
+    // amount (u64) | with FLAG_ALLOWLIST: max_allocation (u64) | proof_len (u8) | proof_len * [u8; 32]
+    if data.len() < 8 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+    let mut amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+
+ // Check if the amount to contribute meets the campaign's minimum
+            if amount < fundraiser_state.min_contribution() {
             return Err(FundRaiserError::ContributionTooSmall.into());
             }
 
 
         // Check if the amount to contribute is less than the maximum allowed contribution
-        let max_per_contributor = (fundraiser_state.amount_to_raise() * constants::MAX_CONTRIBUTION_PERCENTAGE) / constants::PERCENTAGE_SCALER;
+        let max_per_contributor = fundraiser_state.max_contribution_amount()?;
         if amount > max_per_contributor {
             return Err(FundRaiserError::ContributionTooBig.into());
             }
 
-        // Check if the fundraising duration has been reached
-        let current_time = Clock::get()?.unix_timestamp; // i64
-        let started = fundraiser_state.time_started() as i64;
-        let elapsed_days = ((current_time - started) / constants::SECONDS_TO_DAYS) as u8;
-        if fundraiser_state.duration() <= elapsed_days {
-            return Err(FundRaiserError::FundraiserEnded.into());
-            }
+        // Check that the fundraising window is open
+        let now = Clock::get()?.unix_timestamp;
+        match fundraiser_state.phase(now) {
+            FundRaiserPhase::NotStarted => return Err(FundRaiserError::FundraiserNotStarted.into()),
+            FundRaiserPhase::Ended => return Err(FundRaiserError::FundraiserEnded.into()),
+            FundRaiserPhase::Open => {}
+        }
 
         // Check if the maximum contributions per contributor have been reached
         let new_total = contributor_state.amount().saturating_add(amount);
@@ -102,20 +116,71 @@ pub fn process_contribute_instruction(
             return Err(FundRaiserError::MaximumContributionsReached.into());
             }
 
+        // Allowlisted rounds: prove (contributor, max_allocation) and stay within that allocation
+        if fundraiser_state.has_allowlist() {
+            if data.len() < 8 + 8 + 1 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+            let max_allocation = u64::from_le_bytes(data[8..16].try_into().unwrap());
+            let proof_len = data[16] as usize;
+            let proof = data.get(17..17 + proof_len * 32)
+                .ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+
+            if !verify_proof(fundraiser_state.allowlist_root(), allowlist_leaf(contributor.key(), max_allocation), proof) {
+                return Err(FundRaiserError::NotAllowlisted.into());
+            }
+            if new_total > max_allocation {
+                return Err(FundRaiserError::AllocationExceeded.into());
+            }
+        }
+
+        // Never let the raised amount go past the hard cap: clamp when partial fills are allowed
+        if fundraiser_state.hard_cap() != 0 {
+            let remaining = fundraiser_state.hard_cap().saturating_sub(fundraiser_state.current_amount());
+            if remaining == 0 || (amount > remaining && !fundraiser_state.partial_fill()) {
+                return Err(FundRaiserError::HardCapReached.into());
+            }
+            amount = amount.min(remaining);
+        }
+
  // Transfer the funds from the contributor to the vault
  if !contributor.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
-  pinocchio_token::instructions::Transfer {
-        from: contributor_ata,
-        to: vault,
-        authority: contributor,
-        amount: amount,
-    }.invoke()?;
-
+    let received = if fundraiser_state.is_native() {
+        pinocchio_system::instructions::Transfer {
+            from: contributor,
+            to: vault,
+            lamports: amount,
+        }.invoke()?;
+        amount
+    } else {
+        let decimals = mint_decimals(mint_to_raise, token_program)?;
+        let vault_before = token_account_amount(vault, token_program)?;
+        transfer_checked(token_program, contributor_ata, mint_to_raise, vault, contributor, amount, decimals, hook_accounts, &[])?;
+
+        // Credit what actually landed in the vault (net of any Token-2022 transfer fee)
+        token_account_amount(vault, token_program)?.saturating_sub(vault_before)
+    };
 
-    let new_total = contributor_state.amount().saturating_add(amount);
+    let new_total = contributor_state.amount().saturating_add(received);
     contributor_state.set_amount(&new_total);
-    let raised = fundraiser_state.current_amount().saturating_add(amount);
+    contributor_state.set_last_contribution_ts(&now);
+    let raised = fundraiser_state.current_amount().saturating_add(received);
     fundraiser_state.set_current_amount(&raised);
 
+    emit(EventKind::Contributed, &Contributed {
+        fundraiser: *fundraiser.key(),
+        contributor: *contributor.key(),
+        amount: received,
+        contributor_total: new_total,
+        current_amount: raised,
+    });
+
+    // Receipts mirror the amount credited, signed for by the fundraiser as mint authority
+    if let Some((receipt_mint, contributor_receipt)) = receipt_accounts {
+        let maker = fundraiser_state.maker();
+        let campaign_seed = fundraiser_state.campaign_seed();
+        let bump_arr = [fundraiser_state.bump];
+        let seed = [Seed::from(b"fundraiser"), Seed::from(&maker), Seed::from(campaign_seed.as_slice()), Seed::from(&bump_arr)];
+        mint_to(token_program, receipt_mint, contributor_receipt, fundraiser, received, &[Signer::from(&seed)])?;
+    }
+
       Ok(())
 }
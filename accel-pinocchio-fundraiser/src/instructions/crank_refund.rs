@@ -0,0 +1,134 @@
+use pinocchio::{
+    account_info::AccountInfo, instruction::{Seed, Signer}, sysvars::{clock::Clock, Sysvar}, ProgramResult
+};
+use crate::{
+    constants::{BASIS_POINTS_SCALER, CRANK_REFUND_BOUNTY_BPS},
+    error::FundRaiserError,
+    events::{emit, EventKind, Refunded},
+    state::{contributor::Contributor, fundraiser::FundRaiser},
+    token::{check_token_account, has_transfer_hook, mint_decimals, token_account_amount, transfer_checked},
+    utils::{close_program_account, native_vault_balance, transfer_lamports},
+    validation::{check_contributor_account, check_fundraiser_account, check_maker, check_mint, check_program, check_refundable, check_vault},
+};
+
+pub fn process_crank_refund_instruction(
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing CrankRefund instruction");
+
+    let [
+        keeper,
+        maker,
+        mint_to_raise,
+        fundraiser,
+        vault,
+        token_program,
+        system_program,
+        // (contributor_account, contributor, contributor_ata) per contributor, the ATA slot unused for
+        // native SOL campaigns
+        batch @ ..
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !keeper.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+    if batch.is_empty() || batch.len() % 3 != 0 {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    }
+
+    check_fundraiser_account(fundraiser)?;
+    let (native, mint, campaign_seed, bump) = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+        check_maker(fundraiser_state, maker)?;
+        check_mint(fundraiser_state, mint_to_raise, token_program)?;
+        check_vault(fundraiser_state, fundraiser, vault, token_program)?;
+
+        // Receipts can only be burned by their holder
+        if fundraiser_state.has_receipt() {
+            return Err(FundRaiserError::ReceiptCampaign.into());
+        }
+        // The batch leaves no room for hook accounts, so each contributor calls Refund with their own
+        if has_transfer_hook(mint_to_raise)? {
+            return Err(FundRaiserError::TransferHookMint.into());
+        }
+
+        check_refundable(fundraiser_state, Clock::get()?.unix_timestamp)?;
+
+        (fundraiser_state.is_native(), fundraiser_state.mint_to_raise(), fundraiser_state.campaign_seed(), fundraiser_state.bump)
+    };
+    let bump_arr = [bump];
+    let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(campaign_seed.as_slice()), Seed::from(&bump_arr)];
+    let signer = Signer::from(&seed);
+    let decimals = if native { 0 } else { mint_decimals(mint_to_raise, token_program)? };
+
+    for entry in batch.chunks_exact(3) {
+        let [contributor_account, contributor, contributor_ata] = entry else {
+            return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+        };
+
+        check_contributor_account(contributor_account, fundraiser, contributor)?;
+
+        // Already refunded, e.g. by the contributor racing the keeper: nothing left to do
+        if contributor_account.data_is_empty() {
+            continue;
+        }
+        // Tokens only go to the contributor's own token account; one closed or wrong ATA must not hold
+        // up the rest of the batch, so its contributor is left to a later crank or to Refund
+        if !native && check_token_account(contributor_ata, contributor.key(), &mint, token_program).is_err() {
+            continue;
+        }
+
+        // Same pro rata share the contributor would get from Refund
+        let (contrib_amount, payout) = {
+            let contributor_state = Contributor::from_account_info(contributor_account)?;
+            let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+            let vault_amount = if native {
+                native_vault_balance(vault)?
+            } else {
+                token_account_amount(vault, token_program)?
+            };
+            (contributor_state.amount(), fundraiser_state.refund_share(contributor_state.amount(), vault_amount))
+        };
+
+        if payout > 0 {
+            if native {
+                transfer_lamports(vault, contributor, payout)?;
+            } else {
+                transfer_checked(token_program, vault, mint_to_raise, contributor_ata, fundraiser, payout, decimals, &[], &[signer.clone()])?;
+            }
+        }
+
+        let new_total = {
+            let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+            let new_total = fundraiser_state.current_amount().saturating_sub(contrib_amount);
+            fundraiser_state.set_current_amount(&new_total);
+            let refunded = fundraiser_state.refunded_amount()
+                .checked_add(payout)
+                .ok_or(pinocchio::program_error::ProgramError::ArithmeticOverflow)?;
+            fundraiser_state.set_refunded_amount(&refunded);
+            new_total
+        };
+
+        // The keeper's bounty comes out of the record's rent; the contributor gets the rest back
+        {
+            let contributor_state = Contributor::from_account_info(contributor_account)?;
+            contributor_state.set_amount(&0);
+        }
+        let bounty = contributor_account.lamports() * CRANK_REFUND_BOUNTY_BPS / BASIS_POINTS_SCALER;
+        transfer_lamports(contributor_account, keeper, bounty)?;
+        close_program_account(contributor_account, contributor)?;
+
+        emit(EventKind::Refunded, &Refunded {
+            fundraiser: *fundraiser.key(),
+            contributor: *contributor.key(),
+            amount: contrib_amount,
+            payout,
+            current_amount: new_total,
+        });
+    }
+
+    Ok(())
+}
@@ -1,17 +1,16 @@
 use pinocchio::{
-    account_info::AccountInfo, instruction::{Seed, Signer}, msg, pubkey::log, sysvars::{rent::Rent, Sysvar}, ProgramResult
+    account_info::AccountInfo, instruction::{Seed, Signer}, pubkey::create_program_address, sysvars::{clock::Clock, rent::Rent, Sysvar}, ProgramResult
 };
 use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::state::Mint;
 
-use crate::state::fundraiser::FundRaiser;
+use crate::{constants::{BASIS_POINTS_SCALER, MAX_STRETCH_GOALS, MAX_TRANCHES}, error::FundRaiserError, events::{emit, EventKind, Initialized}, state::{fundraiser::{ContributionCapKind, FundRaiser, FundRaiserStatus}, milestones::Milestones}, token::{check_token_program, initialize_mint, mint_decimals, MINT_BASE_LEN}, validation::{check_contribution_limits, check_program, check_stretch_goals}};
 pub fn process_initialize_instruction(
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
 
 
-    msg!("Processing Initialize instruction");
+    trace!("Processing Initialize instruction");
 
     let [
         maker,
@@ -20,31 +19,171 @@ pub fn process_initialize_instruction(
         vault,
         system_program,
         token_program,
-        _associated_token_program,
+        associated_token_program,
+        // optional accounts, in this order: `[b"milestones", fundraiser]` with FLAG_MILESTONES,
+        // `[b"receipt", fundraiser]` with FLAG_RECEIPT
+        optional_accounts @ ..
     ] = accounts else {
         return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
     };
    if !maker.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+    check_program(associated_token_program, &pinocchio_associated_token_account::ID)?;
 
-    let base_seeds = [b"fundraiser".as_ref(), maker.key().as_ref()];
+    // amount_to_raise (u64) | start_ts (i64, 0 = now) | end_ts (i64) | campaign_id (u64)
+    // | min_contribution (u64) | max_per_contributor (u64) | cap_kind (u8: 0 = absolute, 1 = basis points)
+    // | hard_cap (u64, 0 = none) | flags (u8)
+    // | with FLAG_MILESTONES: tranche_count (u8) | tranche_count * (basis_points (u16) | unlock_ts (i64))
+    // | with FLAG_GOVERNANCE: quorum_bps (u16) | approval_threshold_bps (u16)
+    // | with FLAG_ALLOWLIST: allowlist_root ([u8; 32])
+    // | with FLAG_STRETCH_GOALS: stretch_goal_count (u8) | stretch_goal_count * threshold (u64)
+    // | optionally, to enable Withdraw: withdraw_cooldown (i64) | withdraw_penalty_bps (u16)
+    if data.len() < 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+
+    let mut i = 0;
+    let amount_to_raise = u64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+    let start_ts = i64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+    let end_ts = i64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+    let campaign_id = u64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+    let min_contribution = u64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+    let max_per_contributor = u64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+    let cap_kind = ContributionCapKind::try_from(data[i])
+        .map_err(|_| pinocchio::program_error::ProgramError::InvalidInstructionData)?; i += 1;
+    let hard_cap = u64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+    let flags = data[i]; i += 1;
+
+    if hard_cap != 0 && hard_cap < amount_to_raise {
+        return Err(FundRaiserError::InvalidHardCap.into());
+    }
+    let campaign_id_bytes = campaign_id.to_le_bytes();
+
+    let base_seeds = [b"fundraiser".as_ref(), maker.key().as_ref(), campaign_id_bytes.as_ref()];
     let (fundraiser_pda, bump) = pinocchio::pubkey::find_program_address(&base_seeds, &crate::ID);//
-    let fundraiser_account_pda = fundraiser_pda;
-    //if expected_pda != *fundraiser.key() { return Err(pinocchio::program_error::ProgramError::InvalidArgument); }
-    log(&fundraiser_account_pda);
-    log(&fundraiser.key());
-    assert_eq!(fundraiser_account_pda, *fundraiser.key());
+    #[cfg(feature = "logging")]
+    {
+        pinocchio::pubkey::log(&fundraiser_pda);
+        pinocchio::pubkey::log(fundraiser.key());
+    }
+    if fundraiser_pda != *fundraiser.key() {
+        return Err(FundRaiserError::InvalidFundraiser.into());
+    }
  
-let mint = Mint::from_account_info(mint_to_raise)?;
-if !mint.is_initialized() { return Err(pinocchio::program_error::ProgramError::UninitializedAccount); }
+    // The campaign may open later, but never in the past, and must close after it opens
+    let now = Clock::get()?.unix_timestamp;
+    let start_ts = if start_ts == 0 { now } else { start_ts };
+    if start_ts < now || end_ts <= start_ts {
+        return Err(FundRaiserError::InvalidSchedule.into());
+    }
 
+    // Tranches unlock one after another once the campaign is over and add up to the whole raise
+    let milestones = flags & FundRaiser::FLAG_MILESTONES != 0;
+    let mut tranches = [(0u16, 0i64); MAX_TRANCHES];
+    let mut tranche_count = 0;
+    if milestones {
+        tranche_count = *data.get(i).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)? as usize; i += 1;
+        if tranche_count == 0 || tranche_count > MAX_TRANCHES {
+            return Err(FundRaiserError::InvalidMilestoneSchedule.into());
+        }
+        if data.len() < i + tranche_count * (2 + 8) { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+
+        let mut total_basis_points = 0u64;
+        let mut previous_unlock_ts = end_ts - 1;
+        for tranche in tranches.iter_mut().take(tranche_count) {
+            let basis_points = u16::from_le_bytes(data[i..i+2].try_into().unwrap()); i += 2;
+            let unlock_ts = i64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+            if basis_points == 0 || unlock_ts <= previous_unlock_ts {
+                return Err(FundRaiserError::InvalidMilestoneSchedule.into());
+            }
+            total_basis_points += basis_points as u64;
+            previous_unlock_ts = unlock_ts;
+            *tranche = (basis_points, unlock_ts);
+        }
+        if total_basis_points != BASIS_POINTS_SCALER {
+            return Err(FundRaiserError::InvalidMilestoneSchedule.into());
+        }
+    }
+
+    // Releases put to a vote need a quorum and an approval threshold, both in basis points
+    let (mut quorum_bps, mut approval_threshold_bps) = (0u16, 0u16);
+    if flags & FundRaiser::FLAG_GOVERNANCE != 0 {
+        if data.len() < i + 2 + 2 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+        quorum_bps = u16::from_le_bytes(data[i..i+2].try_into().unwrap()); i += 2;
+        approval_threshold_bps = u16::from_le_bytes(data[i..i+2].try_into().unwrap()); i += 2;
+        let valid = 1..=BASIS_POINTS_SCALER as u16;
+        if !valid.contains(&quorum_bps) || !valid.contains(&approval_threshold_bps) {
+            return Err(FundRaiserError::InvalidGovernance.into());
+        }
+    }
+
+    let mut allowlist_root = [0u8; 32];
+    if flags & FundRaiser::FLAG_ALLOWLIST != 0 {
+        if data.len() < i + 32 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+        allowlist_root.copy_from_slice(&data[i..i+32]); i += 32;
+    }
+
+    // Stretch goals are validated against the target and hard cap once the state is written
+    let mut stretch_goals = [0u64; MAX_STRETCH_GOALS];
+    let mut stretch_goal_count = 0;
+    if flags & FundRaiser::FLAG_STRETCH_GOALS != 0 {
+        stretch_goal_count = *data.get(i).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)? as usize; i += 1;
+        if stretch_goal_count == 0 || stretch_goal_count > MAX_STRETCH_GOALS {
+            return Err(FundRaiserError::InvalidStretchGoals.into());
+        }
+        if data.len() < i + stretch_goal_count * 8 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+        for goal in stretch_goals.iter_mut().take(stretch_goal_count) {
+            *goal = u64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+        }
+    }
+
+    // Withdrawal terms are the last section, so campaigns without them end right here
+    let withdrawals = if data.len() > i {
+        if data.len() < i + 8 + 2 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+        let cooldown = i64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
+        let penalty_bps = u16::from_le_bytes(data[i..i+2].try_into().unwrap());
+        // A full penalty would turn a withdrawal into burning the contribution
+        if cooldown < 0 || penalty_bps as u64 >= BASIS_POINTS_SCALER {
+            return Err(FundRaiserError::InvalidWithdrawTerms.into());
+        }
+        Some((cooldown, penalty_bps))
+    } else {
+        None
+    };
+
+    // Native SOL campaigns keep lamports in a program-owned PDA; otherwise the vault is an ATA
+    // and legacy SPL Token and Token-2022 mints are both accepted
+    let native = flags & FundRaiser::FLAG_NATIVE_SOL != 0;
+    let vault_bump = if native {
+        let (vault_pda, vault_bump) = pinocchio::pubkey::find_program_address(&[b"vault", fundraiser.key().as_ref()], &crate::ID);
+        if vault_pda != *vault.key() {
+            return Err(FundRaiserError::InvalidVault.into());
+        }
+        vault_bump
+    } else {
+        check_token_program(token_program)?;
+        mint_decimals(mint_to_raise, token_program)?;
+        let (vault_ata, vault_bump) = pinocchio::pubkey::find_program_address(
+            &[fundraiser.key().as_ref(), token_program.key().as_ref(), mint_to_raise.key().as_ref()],
+            &pinocchio_associated_token_account::ID,
+        );
+        if vault_ata != *vault.key() {
+            return Err(FundRaiserError::InvalidVault.into());
+        }
+        vault_bump
+    };
+
+
+    // Receipts are minted by the fundraiser from a mint of the campaign's token program
+    let receipt = flags & FundRaiser::FLAG_RECEIPT != 0;
+    let receipt_bump = if receipt {
+        check_token_program(token_program)?;
+        pinocchio::pubkey::find_program_address(&[b"receipt", fundraiser.key().as_ref()], &crate::ID).1
+    } else {
+        0
+    };
 
-    let mut i = 0;
-    let amount_to_raise = u64::from_le_bytes(data[i..i+8].try_into().unwrap()); i += 8;
-    let duration = data[i];
-   
     //let bump = [bump.to_le()];
     let bump_arr = [bump];
-    let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(&bump_arr)];//&bump)];
+    let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(&campaign_id_bytes), Seed::from(&bump_arr)];//&bump)];
     let seeds = Signer::from(&seed);
 
     if fundraiser.owner() != &crate::ID {
@@ -60,28 +199,120 @@ if !mint.is_initialized() { return Err(pinocchio::program_error::ProgramError::U
         {
             let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
         
+            fundraiser_state.init_header();
             fundraiser_state.set_maker(maker.key());
-            fundraiser_state.set_mint_to_raise(mint_to_raise.key());
+            fundraiser_state.set_campaign_id(&campaign_id);
+            fundraiser_state.set_mint_to_raise(if native { &[0u8; 32] } else { mint_to_raise.key() });
             fundraiser_state.set_amount_to_raise(&amount_to_raise);
             fundraiser_state.set_current_amount(&0);
-            fundraiser_state.set_time_started(&(pinocchio::sysvars::clock::Clock::get()?.unix_timestamp as u64));
-            fundraiser_state.set_duration(duration);
+            fundraiser_state.set_start_ts(&start_ts);
+            fundraiser_state.set_end_ts(&end_ts);
+            fundraiser_state.set_min_contribution(&min_contribution);
+            fundraiser_state.set_max_per_contributor(&max_per_contributor);
+            fundraiser_state.set_cap_kind(cap_kind);
+            fundraiser_state.set_hard_cap(&hard_cap);
+            fundraiser_state.set_flags(flags);
+            fundraiser_state.set_status(FundRaiserStatus::Active);
+            fundraiser_state.set_quorum_bps(&quorum_bps);
+            fundraiser_state.set_approval_threshold_bps(&approval_threshold_bps);
+            fundraiser_state.set_allowlist_root(&allowlist_root);
+            fundraiser_state.set_stretch_goals(&stretch_goals[..stretch_goal_count])?;
+            if let Some((cooldown, penalty_bps)) = withdrawals {
+                fundraiser_state.set_withdrawals(&cooldown, &penalty_bps);
+            }
             fundraiser_state.bump = bump;
+            fundraiser_state.vault_bump = vault_bump;
+            fundraiser_state.receipt_bump = receipt_bump;
+
+            check_contribution_limits(fundraiser_state)?;
+            check_stretch_goals(fundraiser_state)?;
         }
     }
     else {
         return Err(pinocchio::program_error::ProgramError::IllegalOwner);
     }
 
-    pinocchio_associated_token_account::instructions::Create {
-        funding_account: maker,
-        account: vault,
-        wallet: fundraiser,
-        mint: mint_to_raise,
-        token_program: token_program,
-        system_program: system_program,
-    }.invoke()?;
+    if native {
+        let vault_bump_arr = [vault_bump];
+        let vault_seed = [Seed::from(b"vault"), Seed::from(fundraiser.key()), Seed::from(&vault_bump_arr)];
+        CreateAccount {
+            from: maker,
+            to: vault,
+            lamports: Rent::get()?.minimum_balance(0),
+            space: 0,
+            owner: &crate::ID,
+        }.invoke_signed(&[Signer::from(&vault_seed)])?;
+    } else {
+        pinocchio_associated_token_account::instructions::Create {
+            funding_account: maker,
+            account: vault,
+            wallet: fundraiser,
+            mint: mint_to_raise,
+            token_program: token_program,
+            system_program: system_program,
+        }.invoke()?;
+    }
+
+    let mut optional_accounts = optional_accounts.iter();
+
+    if milestones {
+        let milestones_account = optional_accounts.next().ok_or(pinocchio::program_error::ProgramError::NotEnoughAccountKeys)?;
+        let (milestones_pda, milestones_bump) = pinocchio::pubkey::find_program_address(&[b"milestones", fundraiser.key().as_ref()], &crate::ID);
+        if milestones_pda != *milestones_account.key() {
+            return Err(FundRaiserError::InvalidMilestones.into());
+        }
+
+        let milestones_bump_arr = [milestones_bump];
+        let milestones_seed = [Seed::from(b"milestones"), Seed::from(fundraiser.key()), Seed::from(&milestones_bump_arr)];
+        CreateAccount {
+            from: maker,
+            to: milestones_account,
+            lamports: Rent::get()?.minimum_balance(Milestones::LEN),
+            space: Milestones::LEN as u64,
+            owner: &crate::ID,
+        }.invoke_signed(&[Signer::from(&milestones_seed)])?;
+
+        let milestones_state = Milestones::from_account_info(milestones_account)?;
+        milestones_state.set_tranche_count(tranche_count as u8);
+        for (index, (basis_points, unlock_ts)) in tranches.iter().take(tranche_count).enumerate() {
+            let tranche = milestones_state.tranche_mut(index as u8).ok_or(FundRaiserError::InvalidMilestoneSchedule)?;
+            tranche.set_basis_points(basis_points);
+            tranche.set_unlock_ts(unlock_ts);
+        }
+        milestones_state.bump = milestones_bump;
+    }
+
+    if receipt {
+        let receipt_mint = optional_accounts.next().ok_or(pinocchio::program_error::ProgramError::NotEnoughAccountKeys)?;
+        let receipt_bump_arr = [receipt_bump];
+        let receipt_seed = [Seed::from(b"receipt"), Seed::from(fundraiser.key()), Seed::from(&receipt_bump_arr)];
+        if create_program_address(&[b"receipt", fundraiser.key().as_ref(), &receipt_bump_arr], &crate::ID)? != *receipt_mint.key() {
+            return Err(FundRaiserError::InvalidReceiptMint.into());
+        }
+
+        CreateAccount {
+            from: maker,
+            to: receipt_mint,
+            lamports: Rent::get()?.minimum_balance(MINT_BASE_LEN),
+            space: MINT_BASE_LEN as u64,
+            owner: token_program.key(),
+        }.invoke_signed(&[Signer::from(&receipt_seed)])?;
+
+        // One receipt unit per unit raised, so receipts share the raised asset's decimals
+        let decimals = if native { 9 } else { mint_decimals(mint_to_raise, token_program)? };
+        initialize_mint(token_program, receipt_mint, decimals, fundraiser.key())?;
+    }
 
+    emit(EventKind::Initialized, &Initialized {
+        fundraiser: *fundraiser.key(),
+        maker: *maker.key(),
+        mint_to_raise: if native { [0u8; 32] } else { *mint_to_raise.key() },
+        amount_to_raise,
+        start_ts,
+        end_ts,
+        campaign_id,
+        hard_cap,
+    });
 
     Ok(())
 }
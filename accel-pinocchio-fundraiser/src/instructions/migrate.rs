@@ -0,0 +1,212 @@
+use pinocchio::{
+    account_info::AccountInfo, pubkey::find_program_address, sysvars::{clock::Clock, rent::Rent, Sysvar}, ProgramResult
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::{
+    constants::{ACCOUNT_HEADER_LEN, BASIS_POINTS_SCALER, VOTING_PERIOD},
+    error::FundRaiserError,
+    state::{contributor::Contributor, fundraiser::{ContributionCapKind, FundRaiser, FundRaiserStatus}},
+    token::{check_token_program, mint_decimals, token_account_amount},
+    validation::check_program,
+};
+
+/// The unversioned program capped every contribution at 10% of the target.
+const LEGACY_MAX_CONTRIBUTION_BPS: u64 = BASIS_POINTS_SCALER / 10;
+/// The unversioned program counted its duration in days.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+pub fn process_migrate_instruction(
+    accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing Migrate instruction");
+
+    let [
+        payer,
+        // a fundraiser or contributor record, told apart by size
+        account,
+        system_program,
+        // unversioned fundraisers: mint_to_raise, token_program, vault, for the minimum contribution and
+        // the vault balance;
+        // contributor records: fundraiser, contributor, to find the record's bump; the fundraiser is
+        // writable for unversioned records, which are claimed against it
+        optional_accounts @ ..
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !payer.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+    if account.owner() != &crate::ID {
+        return Err(pinocchio::program_error::ProgramError::IllegalOwner);
+    }
+
+    // The unversioned fundraiser shares no layout with the current one: convert it field by field
+    if account.data_len() == FundRaiser::LEGACY_LEN {
+        return migrate_legacy_fundraiser(payer, account, optional_accounts);
+    }
+
+    // (unversioned, where the old fields end behind the header, new size, header); later versions
+    // only append fields, which start out zeroed
+    let (unversioned, body_end, len, discriminator, version) = match account.data_len() {
+        FundRaiser::LEN_V1 if header(account)? == (FundRaiser::DISCRIMINATOR, 1) =>
+            (false, FundRaiser::LEN_V1, FundRaiser::LEN, FundRaiser::DISCRIMINATOR, FundRaiser::VERSION),
+        FundRaiser::LEN_V2 if header(account)? == (FundRaiser::DISCRIMINATOR, 2) =>
+            (false, FundRaiser::LEN_V2, FundRaiser::LEN, FundRaiser::DISCRIMINATOR, FundRaiser::VERSION),
+        // The unversioned record is just the amount, which lands right behind the header
+        Contributor::LEGACY_LEN =>
+            (true, ACCOUNT_HEADER_LEN + Contributor::LEGACY_LEN, Contributor::LEN, Contributor::DISCRIMINATOR, Contributor::VERSION),
+        Contributor::LEN_V1 if header(account)? == (Contributor::DISCRIMINATOR, 1) =>
+            (false, Contributor::LEN_V1, Contributor::LEN, Contributor::DISCRIMINATOR, Contributor::VERSION),
+        Contributor::LEN_V2 if header(account)? == (Contributor::DISCRIMINATOR, 2) =>
+            (false, Contributor::LEN_V2, Contributor::LEN, Contributor::DISCRIMINATOR, Contributor::VERSION),
+        // Already on the current layout: nothing to do, so batch migrations can be retried safely
+        FundRaiser::LEN if FundRaiser::from_account_info(account)?.is_current() => return Ok(()),
+        Contributor::LEN if Contributor::from_account_info(account)?.is_current() => return Ok(()),
+        _ => return Err(FundRaiserError::InvalidAccountVersion.into()),
+    };
+
+    // Contributor records store their bump from version 3 on; the record has to be the canonical PDA
+    let contributor_bump = if discriminator == Contributor::DISCRIMINATOR {
+        let [fundraiser, contributor, ..] = optional_accounts else {
+            return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+        };
+        let (expected, bump) = find_program_address(
+            &[b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref()],
+            &crate::ID,
+        );
+        if account.key() != &expected {
+            return Err(FundRaiserError::InvalidContributorAccount.into());
+        }
+        if unversioned {
+            claim_legacy_amount(account, fundraiser)?;
+        }
+        Some(bump)
+    } else {
+        None
+    };
+
+    // Grow the account, shift unversioned fields behind the header and stamp the new version
+    let old_len = account.data_len();
+    grow(payer, account, len)?;
+    {
+        let mut data = account.try_borrow_mut_data()?;
+        if unversioned {
+            data.copy_within(0..old_len, ACCOUNT_HEADER_LEN);
+        }
+        data[body_end..].fill(0);
+        data[0] = discriminator;
+        data[1] = version;
+    }
+    if let Some(bump) = contributor_bump {
+        Contributor::from_account_info(account)?.bump = bump;
+    }
+    // A release already under way when voting got a deadline is given a full voting period
+    if discriminator == FundRaiser::DISCRIMINATOR {
+        let fundraiser_state = FundRaiser::from_account_info(account)?;
+        if fundraiser_state.vote_round() > 0 {
+            fundraiser_state.set_vote_end_ts(&Clock::get()?.unix_timestamp.saturating_add(VOTING_PERIOD));
+        }
+    }
+
+    Ok(())
+}
+
+// maker (32) | mint_to_raise (32) | amount_to_raise (8) | current_amount (8) | time_started (u64) |
+// duration (u8, days) | bump (1), living at `[b"fundraiser", maker]`
+fn migrate_legacy_fundraiser(payer: &AccountInfo, account: &AccountInfo, optional_accounts: &[AccountInfo]) -> ProgramResult {
+    let [mint_to_raise, token_program, vault, ..] = optional_accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    let mut legacy = [0u8; FundRaiser::LEGACY_LEN];
+    legacy.copy_from_slice(&account.try_borrow_data()?);
+    let maker: [u8; 32] = legacy[0..32].try_into().unwrap();
+    let mint: [u8; 32] = legacy[32..64].try_into().unwrap();
+    let amount_to_raise = u64::from_le_bytes(legacy[64..72].try_into().unwrap());
+    let current_amount = u64::from_le_bytes(legacy[72..80].try_into().unwrap());
+    let start_ts = u64::from_le_bytes(legacy[80..88].try_into().unwrap()) as i64;
+    let end_ts = start_ts + legacy[88] as i64 * SECONDS_PER_DAY;
+    let bump = legacy[89];
+
+    if mint_to_raise.key() != &mint {
+        return Err(FundRaiserError::InvalidMint.into());
+    }
+    check_token_program(token_program)?;
+    // The unversioned program asked for at least one whole token
+    let min_contribution = 10_u64.pow(mint_decimals(mint_to_raise, token_program)? as u32);
+    let (expected_vault, vault_bump) = find_program_address(
+        &[account.key().as_ref(), token_program.key().as_ref(), mint.as_ref()],
+        &pinocchio_associated_token_account::ID,
+    );
+    if vault.key() != &expected_vault {
+        return Err(FundRaiserError::InvalidVault.into());
+    }
+    // The layout has no status: a campaign whose vault no longer backs its total was already paid
+    // out to the maker, and must not come back open
+    let vault_amount = if vault.data_is_empty() { 0 } else { token_account_amount(vault, token_program)? };
+    if vault_amount < current_amount {
+        return Err(FundRaiserError::LegacyVaultDrained.into());
+    }
+
+    grow(payer, account, FundRaiser::LEN)?;
+    account.try_borrow_mut_data()?.fill(0);
+
+    let fundraiser_state = FundRaiser::from_account_info(account)?;
+    fundraiser_state.init_header();
+    fundraiser_state.set_maker(&maker);
+    fundraiser_state.set_mint_to_raise(&mint);
+    fundraiser_state.set_amount_to_raise(&amount_to_raise);
+    fundraiser_state.set_current_amount(&current_amount);
+    fundraiser_state.set_start_ts(&start_ts);
+    fundraiser_state.set_end_ts(&end_ts);
+    fundraiser_state.set_min_contribution(&min_contribution);
+    fundraiser_state.set_max_per_contributor(&LEGACY_MAX_CONTRIBUTION_BPS);
+    fundraiser_state.set_cap_kind(ContributionCapKind::BasisPoints);
+    fundraiser_state.set_status(FundRaiserStatus::Active);
+    fundraiser_state.set_legacy_address();
+    fundraiser_state.set_legacy_unclaimed_amount(&current_amount);
+    fundraiser_state.bump = bump;
+    fundraiser_state.vault_bump = vault_bump;
+
+    Ok(())
+}
+
+// An unversioned record carries over only while its fundraiser, converted first, still has that much
+// unclaimed: the old refund paid out without zeroing the record, so the records of a campaign that
+// refunded add up to more than what is left in it
+fn claim_legacy_amount(account: &AccountInfo, fundraiser: &AccountInfo) -> ProgramResult {
+    if fundraiser.owner() != &crate::ID || fundraiser.data_len() != FundRaiser::LEN {
+        return Err(FundRaiserError::InvalidAccountVersion.into());
+    }
+    let amount = u64::from_le_bytes(account.try_borrow_data()?[..Contributor::LEGACY_LEN].try_into().unwrap());
+    let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+    if !fundraiser_state.is_current() || !fundraiser_state.has_legacy_address() {
+        return Err(FundRaiserError::InvalidAccountVersion.into());
+    }
+    let unclaimed = fundraiser_state.legacy_unclaimed_amount()
+        .checked_sub(amount)
+        .ok_or(FundRaiserError::LegacyAmountExceeded)?;
+    fundraiser_state.set_legacy_unclaimed_amount(&unclaimed);
+    Ok(())
+}
+
+// The payer tops up rent for the larger layout before the account grows
+fn grow(payer: &AccountInfo, account: &AccountInfo, len: usize) -> ProgramResult {
+    let minimum_balance = Rent::get()?.minimum_balance(len);
+    if account.lamports() < minimum_balance {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: minimum_balance - account.lamports(),
+        }.invoke()?;
+    }
+    account.resize(len)
+}
+
+fn header(account: &AccountInfo) -> Result<(u8, u8), pinocchio::program_error::ProgramError> {
+    let data = account.try_borrow_data()?;
+    Ok((data[0], data[1]))
+}
@@ -2,12 +2,30 @@ pub mod initialize;
 pub mod contribute;
 pub mod checker;
 pub mod refund;
+pub mod cancel;
+pub mod claim_tranche;
+pub mod vote;
+pub mod set_config;
+pub mod migrate;
+pub mod update;
+pub mod crank_refund;
+pub mod withdraw;
+pub mod close;
 // pub mod make_2;
 
 pub use initialize::*;
 pub use contribute::*;
 pub use checker::*;
 pub use refund::*;
+pub use cancel::*;
+pub use claim_tranche::*;
+pub use vote::*;
+pub use set_config::*;
+pub use migrate::*;
+pub use update::*;
+pub use crank_refund::*;
+pub use withdraw::*;
+pub use close::*;
 // pub use make_2::*;
 
 pub enum FundRaiserInstrctions {
@@ -15,6 +33,15 @@ pub enum FundRaiserInstrctions {
     Contribute = 1,
     Checker = 2,
     Refund = 3,
+    Cancel = 4,
+    ClaimTranche = 5,
+    Vote = 6,
+    SetConfig = 7,
+    Migrate = 8,
+    Update = 9,
+    CrankRefund = 10,
+    Withdraw = 11,
+    Close = 12,
 }
 
 impl TryFrom<&u8> for FundRaiserInstrctions {
@@ -26,6 +53,15 @@ impl TryFrom<&u8> for FundRaiserInstrctions {
             1 => Ok(FundRaiserInstrctions::Contribute),
             2 => Ok(FundRaiserInstrctions::Checker),
             3 => Ok(FundRaiserInstrctions::Refund),
+            4 => Ok(FundRaiserInstrctions::Cancel),
+            5 => Ok(FundRaiserInstrctions::ClaimTranche),
+            6 => Ok(FundRaiserInstrctions::Vote),
+            7 => Ok(FundRaiserInstrctions::SetConfig),
+            8 => Ok(FundRaiserInstrctions::Migrate),
+            9 => Ok(FundRaiserInstrctions::Update),
+            10 => Ok(FundRaiserInstrctions::CrankRefund),
+            11 => Ok(FundRaiserInstrctions::Withdraw),
+            12 => Ok(FundRaiserInstrctions::Close),
             _ => Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
         }
     }
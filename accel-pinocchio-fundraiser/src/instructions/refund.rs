@@ -1,15 +1,18 @@
 use pinocchio::{
-    account_info::AccountInfo, instruction::{Seed, Signer}, msg, sysvars::{clock::Clock, Sysvar}, ProgramResult
+    account_info::AccountInfo, instruction::{Seed, Signer}, sysvars::{clock::Clock, Sysvar}, ProgramResult
 };
-use pinocchio_token::state::TokenAccount;
-use crate::{error::FundRaiserError, state::fundraiser::FundRaiser,constants};
+use crate::{error::FundRaiserError, state::fundraiser::FundRaiser};
 use crate::state::contributor::Contributor;
+use crate::events::{emit, EventKind, Refunded};
+use crate::utils::{close_program_account, native_vault_balance, transfer_lamports};
+use crate::token::{burn, check_token_account, mint_decimals, token_account_amount, transfer_checked};
+use crate::validation::{check_contributor_account, check_fundraiser_account, check_maker, check_mint, check_program, check_receipt_mint, check_refundable, check_vault};
 
 pub fn process_refund_instruction(
     accounts: &[AccountInfo],
     data: &[u8],
 ) -> ProgramResult {
-    msg!("Processing Refund instruction");
+    trace!("Processing Refund instruction");
 
 let [
         contributor,
@@ -21,55 +24,107 @@ let [
         vault,
         token_program,
         system_program,
-       
+        // with FLAG_RECEIPT: receipt_mint, contributor_receipt; then transfer-hook extra accounts,
+        // forwarded to the token program
+        remaining @ ..
     ] = accounts else {
         return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
     };
 
+    if !contributor.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+
+    check_fundraiser_account(fundraiser)?;
+    let (receipt_accounts, hook_accounts) = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+        check_maker(fundraiser_state, maker)?;
+        check_mint(fundraiser_state, mint_to_raise, token_program)?;
+        check_vault(fundraiser_state, fundraiser, vault, token_program)?;
+        if fundraiser_state.has_receipt() {
+            let [receipt_mint, contributor_receipt, hook_accounts @ ..] = remaining else {
+                return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+            };
+            check_receipt_mint(fundraiser_state, fundraiser, receipt_mint, token_program)?;
+            // Receipts only go to, and are only taken from, the contributor's own receipt account
+            check_token_account(contributor_receipt, contributor.key(), receipt_mint.key(), token_program)?;
+            (Some((receipt_mint, contributor_receipt)), hook_accounts)
+        } else {
+            (None, remaining)
+        }
+    };
+
+    // The contributor record must be the canonical PDA: [b"contributor", fundraiser, contributor]
+    check_contributor_account(contributor_account, fundraiser, contributor)?;
+
+    // A closed (or never funded) record means there is nothing left to refund
+    if contributor_account.data_is_empty() {
+        return Err(FundRaiserError::AlreadyRefunded.into());
+    }
+
     // Read and validate state within a limited scope to avoid holding borrows over CPI
-    let (contrib_amount, bump) = {
+    let (native, contrib_amount, payout, campaign_seed, bump) = {
         let contributor_state = Contributor::from_account_info(contributor_account)?;
+        if contributor_state.amount() == 0 {
+            return Err(FundRaiserError::AlreadyRefunded.into());
+        }
         let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+        check_refundable(fundraiser_state, Clock::get()?.unix_timestamp)?;
 
-        // Check if the fundraising duration has been reached
-        let now = Clock::get()?.unix_timestamp; // i64
-        let started = fundraiser_state.time_started() as i64;
-        let elapsed_days = ((now - started) / (crate::constants::SECONDS_TO_DAYS as i64)) as u8;
-        if elapsed_days < fundraiser_state.duration() {
-            return Err(FundRaiserError::FundraiserNotEnded.into());
-        }
+        let vault_amount = if fundraiser_state.is_native() {
+            native_vault_balance(vault)?
+        } else {
+            token_account_amount(vault, token_program)?
+        };
 
-        // Ensure target not met
-        {
-            let vault_acc = TokenAccount::from_account_info(vault)?;
-            let vault_amount = vault_acc.amount();
-            if vault_amount >= fundraiser_state.amount_to_raise() {
-                return Err(FundRaiserError::TargetMet.into());
-            }
-        }
+        // A vault short of what was contributed (released tranches, fees, ...) is shared pro rata
+        let payout = fundraiser_state.refund_share(contributor_state.amount(), vault_amount);
 
-        (contributor_state.amount(), fundraiser_state.bump)
+        (fundraiser_state.is_native(), contributor_state.amount(), payout, fundraiser_state.campaign_seed(), fundraiser_state.bump)
     };
 
+    // The contributor hands back the receipts for the whole contribution
+    if let Some((receipt_mint, contributor_receipt)) = receipt_accounts {
+        burn(token_program, contributor_receipt, receipt_mint, contributor, contrib_amount, &[])?;
+    }
+
     // Transfer the funds from the vault to the contributor (no outstanding borrows now)
-    let bump_arr = [bump];
-    let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(&bump_arr)];
-    let signer = Signer::from(&seed);
-
-    pinocchio_token::instructions::Transfer {
-        from: vault,
-        to: contributor_ata,
-        authority: fundraiser,
-        amount: contrib_amount,
+    if native {
+        transfer_lamports(vault, contributor, payout)?;
+    } else {
+        let bump_arr = [bump];
+        let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(campaign_seed.as_slice()), Seed::from(&bump_arr)];
+        let signer = Signer::from(&seed);
+
+        let decimals = mint_decimals(mint_to_raise, token_program)?;
+        transfer_checked(token_program, vault, mint_to_raise, contributor_ata, fundraiser, payout, decimals, hook_accounts, &[signer])?;
     }
-    .invoke_signed(&[signer])?;
 
-    // Update the fundraiser state by reducing the amount contributed
-    {
+    // Update the fundraiser state by reducing the amount contributed and recording the payout
+    let new_total = {
         let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
         let new_total = fundraiser_state.current_amount().saturating_sub(contrib_amount);
         fundraiser_state.set_current_amount(&new_total);
+        let refunded = fundraiser_state.refunded_amount()
+            .checked_add(payout)
+            .ok_or(pinocchio::program_error::ProgramError::ArithmeticOverflow)?;
+        fundraiser_state.set_refunded_amount(&refunded);
+        new_total
+    };
+
+    // Zero the record and close the contributor PDA, returning its rent to the contributor
+    {
+        let contributor_state = Contributor::from_account_info(contributor_account)?;
+        contributor_state.set_amount(&0);
     }
+    close_program_account(contributor_account, contributor)?;
+
+    emit(EventKind::Refunded, &Refunded {
+        fundraiser: *fundraiser.key(),
+        contributor: *contributor.key(),
+        amount: contrib_amount,
+        payout,
+        current_amount: new_total,
+    });
 
   Ok(())
 }
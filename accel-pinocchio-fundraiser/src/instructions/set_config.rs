@@ -0,0 +1,66 @@
+use pinocchio::{
+    account_info::AccountInfo, instruction::{Seed, Signer}, pubkey::Pubkey, sysvars::{rent::Rent, Sysvar}, ProgramResult
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{constants::MAX_PLATFORM_FEE_BPS, error::FundRaiserError, state::config::Config, validation::{check_config_account, check_program, check_upgrade_authority}};
+
+pub fn process_set_config_instruction(
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing SetConfig instruction");
+
+    let [
+        admin,
+        config,
+        system_program,
+        // the program's ProgramData account, checked when the config is created
+        program_data,
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !admin.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+
+    // fee_bps (u16) | fee_recipient ([u8; 32]) | new_admin ([u8; 32])
+    if data.len() < 2 + 32 + 32 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+    let fee_bps = u16::from_le_bytes(data[0..2].try_into().unwrap());
+    let fee_recipient: Pubkey = data[2..34].try_into().unwrap();
+    let new_admin: Pubkey = data[34..66].try_into().unwrap();
+
+    if fee_bps > MAX_PLATFORM_FEE_BPS {
+        return Err(FundRaiserError::InvalidFee.into());
+    }
+
+    // Only the program's upgrade authority can create the config, so nobody can front-run the
+    // deployment and take the admin role; afterwards only the admin can change it, including
+    // handing the role over
+    if check_config_account(config)? {
+        if Config::from_account_info(config)?.admin() != *admin.key() {
+            return Err(FundRaiserError::Unauthorized.into());
+        }
+    } else {
+        check_upgrade_authority(program_data, admin)?;
+        let (_, bump) = pinocchio::pubkey::find_program_address(&[b"config"], &crate::ID);
+        let bump_arr = [bump];
+        let seed = [Seed::from(b"config"), Seed::from(&bump_arr)];
+        CreateAccount {
+            from: admin,
+            to: config,
+            lamports: Rent::get()?.minimum_balance(Config::LEN),
+            space: Config::LEN as u64,
+            owner: &crate::ID,
+        }.invoke_signed(&[Signer::from(&seed)])?;
+        Config::from_account_info(config)?.bump = bump;
+    }
+
+    let config_state = Config::from_account_info(config)?;
+    config_state.set_admin(&new_admin);
+    config_state.set_fee_recipient(&fee_recipient);
+    config_state.set_fee_bps(&fee_bps);
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use pinocchio::{
+    account_info::AccountInfo, sysvars::{clock::Clock, Sysvar}, ProgramResult
+};
+use crate::{
+    error::FundRaiserError,
+    events::{emit, EventKind, Updated},
+    state::{fundraiser::{ContributionCapKind, FundRaiser, FundRaiserPhase}, milestones::Milestones},
+    validation::{check_contribution_limits, check_fundraiser_account, check_milestones_account, check_stretch_goals},
+};
+
+pub fn process_update_instruction(
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing Update instruction");
+
+    let [
+        maker,
+        fundraiser,
+        // with FLAG_MILESTONES: `[b"milestones", fundraiser]`, to keep the deadline before the first unlock
+        optional_accounts @ ..
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !maker.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+
+    // amount_to_raise (u64) | end_ts (i64) | min_contribution (u64) | max_per_contributor (u64)
+    // | cap_kind (u8: 0 = absolute, 1 = basis points) | hard_cap (u64, 0 = none)
+    if data.len() < 8 + 8 + 8 + 8 + 1 + 8 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+    let amount_to_raise = u64::from_le_bytes(data[0..8].try_into().unwrap());
+    let end_ts = i64::from_le_bytes(data[8..16].try_into().unwrap());
+    let min_contribution = u64::from_le_bytes(data[16..24].try_into().unwrap());
+    let max_per_contributor = u64::from_le_bytes(data[24..32].try_into().unwrap());
+    let cap_kind = ContributionCapKind::try_from(data[32])
+        .map_err(|_| pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+    let hard_cap = u64::from_le_bytes(data[33..41].try_into().unwrap());
+
+    check_fundraiser_account(fundraiser)?;
+    let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+
+    // Only the maker can change the campaign, and only while it is still running
+    if fundraiser_state.maker() != *maker.key() {
+        return Err(FundRaiserError::Unauthorized.into());
+    }
+    if fundraiser_state.is_cancelled() {
+        return Err(FundRaiserError::FundraiserCancelled.into());
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if fundraiser_state.phase(now) == FundRaiserPhase::Ended {
+        return Err(FundRaiserError::FundraiserEnded.into());
+    }
+    if end_ts <= now || end_ts <= fundraiser_state.start_ts() {
+        return Err(FundRaiserError::InvalidSchedule.into());
+    }
+
+    // Contributors signed up for the terms in place: from then on the deadline may only move out
+    if fundraiser_state.current_amount() != 0 {
+        if amount_to_raise != fundraiser_state.amount_to_raise()
+            || min_contribution != fundraiser_state.min_contribution()
+            || max_per_contributor != fundraiser_state.max_per_contributor()
+            || cap_kind != fundraiser_state.cap_kind()?
+            || hard_cap != fundraiser_state.hard_cap()
+        {
+            return Err(FundRaiserError::ParametersLocked.into());
+        }
+        if end_ts < fundraiser_state.end_ts() {
+            return Err(FundRaiserError::DeadlineShortened.into());
+        }
+    }
+
+    // Tranches unlock after the campaign ends, so the deadline cannot move past the first one
+    if fundraiser_state.has_milestones() {
+        let milestones = optional_accounts.first().ok_or(pinocchio::program_error::ProgramError::NotEnoughAccountKeys)?;
+        check_milestones_account(fundraiser, milestones)?;
+        let milestones_state = Milestones::from_account_info(milestones)?;
+        let first_unlock_ts = milestones_state.tranche(0).ok_or(FundRaiserError::InvalidMilestones)?.unlock_ts();
+        if first_unlock_ts < end_ts {
+            return Err(FundRaiserError::InvalidMilestoneSchedule.into());
+        }
+    }
+
+    fundraiser_state.set_amount_to_raise(&amount_to_raise);
+    fundraiser_state.set_end_ts(&end_ts);
+    fundraiser_state.set_min_contribution(&min_contribution);
+    fundraiser_state.set_max_per_contributor(&max_per_contributor);
+    fundraiser_state.set_cap_kind(cap_kind);
+    fundraiser_state.set_hard_cap(&hard_cap);
+    check_contribution_limits(fundraiser_state)?;
+    check_stretch_goals(fundraiser_state)?;
+
+    emit(EventKind::Updated, &Updated {
+        fundraiser: *fundraiser.key(),
+        maker: *maker.key(),
+        amount_to_raise,
+        end_ts,
+        min_contribution,
+        max_per_contributor,
+        hard_cap,
+        cap_kind: cap_kind as u8,
+        _padding: [0; 7],
+    });
+
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use pinocchio::{
+    account_info::AccountInfo, sysvars::{clock::Clock, Sysvar}, ProgramResult
+};
+use crate::{
+    error::FundRaiserError,
+    events::{emit, EventKind, Voted},
+    state::{contributor::Contributor, fundraiser::{FundRaiser, FundRaiserPhase, FundRaiserStatus}},
+    validation::{check_contributor_account, check_fundraiser_account},
+};
+
+pub fn process_vote_instruction(
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing Vote instruction");
+
+    let [
+        contributor,
+        fundraiser,
+        contributor_account,
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !contributor.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+
+    // approve (u8: 0 = reject, 1 = approve)
+    let approve = match data.first() {
+        Some(0) => false,
+        Some(1) => true,
+        _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+    };
+
+    check_fundraiser_account(fundraiser)?;
+    check_contributor_account(contributor_account, fundraiser, contributor)?;
+    if contributor_account.data_is_empty() {
+        return Err(FundRaiserError::AlreadyRefunded.into());
+    }
+
+    let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+    if !fundraiser_state.is_governed() {
+        return Err(FundRaiserError::GovernanceDisabled.into());
+    }
+    if fundraiser_state.is_cancelled() {
+        return Err(FundRaiserError::FundraiserCancelled.into());
+    }
+    if fundraiser_state.is_rejected() {
+        return Err(FundRaiserError::ReleaseRejected.into());
+    }
+
+    // Only a successful campaign has releases to vote on, until voting on the release closes
+    let now = Clock::get()?.unix_timestamp;
+    if fundraiser_state.phase(now) != FundRaiserPhase::Ended {
+        return Err(FundRaiserError::FundraiserNotEnded.into());
+    }
+    if !fundraiser_state.target_met() {
+        return Err(FundRaiserError::TargetNotMet.into());
+    }
+    if now >= fundraiser_state.vote_end_ts() {
+        return Err(FundRaiserError::VotingClosed.into());
+    }
+
+    // One vote per contributor and release, weighted by the amount contributed
+    let contributor_state = Contributor::from_account_info(contributor_account)?;
+    let round = fundraiser_state.vote_round().wrapping_add(1);
+    if contributor_state.voted_round() == round {
+        return Err(FundRaiserError::AlreadyVoted.into());
+    }
+    contributor_state.set_voted_round(round);
+    fundraiser_state.record_vote(contributor_state.amount(), approve)?;
+
+    // Once approval is out of reach the release is dead and backers get the vault back
+    if fundraiser_state.release_rejected() {
+        fundraiser_state.set_status(FundRaiserStatus::Rejected);
+    }
+
+    emit(EventKind::Voted, &Voted {
+        fundraiser: *fundraiser.key(),
+        contributor: *contributor.key(),
+        weight: contributor_state.amount(),
+        votes_for: fundraiser_state.votes_for(),
+        votes_against: fundraiser_state.votes_against(),
+        vote_round: fundraiser_state.vote_round(),
+        approve: approve as u8,
+        _padding: [0; 6],
+    });
+
+    Ok(())
+}
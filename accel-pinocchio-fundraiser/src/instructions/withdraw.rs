@@ -0,0 +1,157 @@
+use pinocchio::{
+    account_info::AccountInfo, instruction::{Seed, Signer}, sysvars::{clock::Clock, Sysvar}, ProgramResult
+};
+use crate::{
+    error::FundRaiserError,
+    events::{emit, EventKind, Withdrawn},
+    state::{contributor::Contributor, fundraiser::{FundRaiser, FundRaiserPhase}},
+    token::{burn, check_token_account, mint_decimals, transfer_checked},
+    utils::{close_program_account, transfer_lamports},
+    validation::{check_contributor_account, check_fundraiser_account, check_maker, check_mint, check_program, check_receipt_mint, check_vault},
+};
+
+pub fn process_withdraw_instruction(
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+
+    trace!("Processing Withdraw instruction");
+
+    let [
+        contributor,
+        maker,
+        mint_to_raise,
+        fundraiser,
+        contributor_account,
+        contributor_ata,
+        vault,
+        token_program,
+        system_program,
+        // with FLAG_RECEIPT: receipt_mint, contributor_receipt; then transfer-hook extra accounts,
+        // forwarded to the token program
+        remaining @ ..
+    ] = accounts else {
+        return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !contributor.is_signer() { return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature); }
+    check_program(system_program, &pinocchio_system::ID)?;
+
+    // amount (u64)
+    if data.len() < 8 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+    let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
+    if amount == 0 { return Err(pinocchio::program_error::ProgramError::InvalidInstructionData); }
+
+    check_fundraiser_account(fundraiser)?;
+    let (receipt_accounts, hook_accounts) = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+        check_maker(fundraiser_state, maker)?;
+        check_mint(fundraiser_state, mint_to_raise, token_program)?;
+        check_vault(fundraiser_state, fundraiser, vault, token_program)?;
+        if fundraiser_state.has_receipt() {
+            let [receipt_mint, contributor_receipt, hook_accounts @ ..] = remaining else {
+                return Err(pinocchio::program_error::ProgramError::NotEnoughAccountKeys);
+            };
+            check_receipt_mint(fundraiser_state, fundraiser, receipt_mint, token_program)?;
+            // Receipts only go to, and are only taken from, the contributor's own receipt account
+            check_token_account(contributor_receipt, contributor.key(), receipt_mint.key(), token_program)?;
+            (Some((receipt_mint, contributor_receipt)), hook_accounts)
+        } else {
+            (None, remaining)
+        }
+    };
+
+    check_contributor_account(contributor_account, fundraiser, contributor)?;
+    if contributor_account.data_is_empty() {
+        return Err(FundRaiserError::WithdrawalTooBig.into());
+    }
+
+    let (native, penalty, contributor_total, campaign_seed, bump) = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+        let contributor_state = Contributor::from_account_info(contributor_account)?;
+
+        if !fundraiser_state.allows_withdrawals() {
+            return Err(FundRaiserError::WithdrawalsDisabled.into());
+        }
+        if fundraiser_state.is_cancelled() {
+            return Err(FundRaiserError::FundraiserCancelled.into());
+        }
+
+        // Only while the campaign can still take contributions: once it ends, Checker or Refund take over
+        let now = Clock::get()?.unix_timestamp;
+        match fundraiser_state.phase(now) {
+            FundRaiserPhase::NotStarted => return Err(FundRaiserError::FundraiserNotStarted.into()),
+            FundRaiserPhase::Ended => return Err(FundRaiserError::FundraiserEnded.into()),
+            FundRaiserPhase::Open => {}
+        }
+        if now < contributor_state.last_contribution_ts().saturating_add(fundraiser_state.withdraw_cooldown()) {
+            return Err(FundRaiserError::WithdrawalCooldown.into());
+        }
+
+        // Withdraw everything, or leave at least the minimum contribution in
+        let contributor_total = contributor_state.amount()
+            .checked_sub(amount)
+            .ok_or(FundRaiserError::WithdrawalTooBig)?;
+        if contributor_total != 0 && contributor_total < fundraiser_state.min_contribution() {
+            return Err(FundRaiserError::ContributionTooSmall.into());
+        }
+
+        (
+            fundraiser_state.is_native(),
+            fundraiser_state.withdraw_penalty(amount),
+            contributor_total,
+            fundraiser_state.campaign_seed(),
+            fundraiser_state.bump,
+        )
+    };
+
+    // The contributor hands back the receipts for what they take out
+    if let Some((receipt_mint, contributor_receipt)) = receipt_accounts {
+        burn(token_program, contributor_receipt, receipt_mint, contributor, amount, &[])?;
+    }
+
+    // The contributor gets the amount net of the penalty, which stays in the vault and counts toward the
+    // target: Checker pays it to the maker with the raise, refunds share it among the remaining
+    // contributors, and Close hands it to the maker if nobody is left to refund
+    if native {
+        transfer_lamports(vault, contributor, amount - penalty)?;
+    } else {
+        let bump_arr = [bump];
+        let seed = [Seed::from(b"fundraiser"), Seed::from(maker.key()), Seed::from(campaign_seed.as_slice()), Seed::from(&bump_arr)];
+        let signer = Signer::from(&seed);
+
+        let decimals = mint_decimals(mint_to_raise, token_program)?;
+        transfer_checked(token_program, vault, mint_to_raise, contributor_ata, fundraiser, amount - penalty, decimals, hook_accounts, &[signer])?;
+    }
+
+    let new_total = {
+        let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+        let new_total = fundraiser_state.current_amount().saturating_sub(amount);
+        fundraiser_state.set_current_amount(&new_total);
+        let penalty_amount = fundraiser_state.penalty_amount()
+            .checked_add(penalty)
+            .ok_or(pinocchio::program_error::ProgramError::ArithmeticOverflow)?;
+        fundraiser_state.set_penalty_amount(&penalty_amount);
+        new_total
+    };
+
+    // A fully withdrawn record is closed, returning its rent like Refund does
+    {
+        let contributor_state = Contributor::from_account_info(contributor_account)?;
+        contributor_state.set_amount(&contributor_total);
+    }
+    if contributor_total == 0 {
+        close_program_account(contributor_account, contributor)?;
+    }
+
+    emit(EventKind::Withdrawn, &Withdrawn {
+        fundraiser: *fundraiser.key(),
+        contributor: *contributor.key(),
+        amount,
+        penalty,
+        contributor_total,
+        current_amount: new_total,
+    });
+
+    Ok(())
+}
@@ -2,11 +2,27 @@ use pinocchio::{account_info::AccountInfo, entrypoint, pubkey::Pubkey, ProgramRe
 
 use crate::instructions::FundRaiserInstrctions;
 
+/// `msg!` that only costs compute units when the `logging` feature is enabled.
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "logging")]
+        pinocchio::msg!($($arg)*);
+    };
+}
+
 mod state;
 mod instructions;
 mod error;
 mod constants;
+mod utils;
+mod token;
+mod validation;
+mod merkle;
+pub mod events;
+#[cfg(any(test, feature = "client"))]
+pub mod client;
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pinocchio_pubkey::declare_id!("9rcdaF2bdQVq3TjrL756VqcZWWYgLdZXJX79soxNoUjr");
@@ -31,6 +47,24 @@ pub fn process_instruction(
             instructions::process_checker_instruction(accounts, data)?,
         FundRaiserInstrctions::Refund =>
             instructions::process_refund_instruction(accounts, data)?,
+        FundRaiserInstrctions::Cancel =>
+            instructions::process_cancel_instruction(accounts, data)?,
+        FundRaiserInstrctions::ClaimTranche =>
+            instructions::process_claim_tranche_instruction(accounts, data)?,
+        FundRaiserInstrctions::Vote =>
+            instructions::process_vote_instruction(accounts, data)?,
+        FundRaiserInstrctions::SetConfig =>
+            instructions::process_set_config_instruction(accounts, data)?,
+        FundRaiserInstrctions::Migrate =>
+            instructions::process_migrate_instruction(accounts, data)?,
+        FundRaiserInstrctions::Update =>
+            instructions::process_update_instruction(accounts, data)?,
+        FundRaiserInstrctions::CrankRefund =>
+            instructions::process_crank_refund_instruction(accounts, data)?,
+        FundRaiserInstrctions::Withdraw =>
+            instructions::process_withdraw_instruction(accounts, data)?,
+        FundRaiserInstrctions::Close =>
+            instructions::process_close_instruction(accounts, data)?,
         _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
     }
     Ok(())
@@ -0,0 +1,21 @@
+use pinocchio::pubkey::Pubkey;
+use solana_sha256_hasher::hashv;
+
+// Leaves and inner nodes are hashed under different prefixes so a node can never pass as a leaf
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Leaf of the allowlist tree: `sha256(0x00 | contributor | max_allocation (u64 LE))`.
+pub fn allowlist_leaf(contributor: &Pubkey, max_allocation: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, contributor.as_ref(), &max_allocation.to_le_bytes()]).to_bytes()
+}
+
+/// Walks `proof` (concatenated 32-byte siblings) up from `leaf`, hashing each pair in sorted
+/// order, and compares against `root`.
+pub fn verify_proof(root: &[u8; 32], leaf: [u8; 32], proof: &[u8]) -> bool {
+    let computed = proof.chunks_exact(32).fold(leaf, |node, sibling| {
+        let (left, right) = if node.as_slice() <= sibling { (node.as_slice(), sibling) } else { (sibling, node.as_slice()) };
+        hashv(&[NODE_PREFIX, left, right]).to_bytes()
+    });
+    computed == *root
+}
@@ -0,0 +1,73 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+
+/// Platform-wide settings, stored at `[b"config"]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Config {
+
+     admin: [u8; 32],
+     fee_recipient: [u8; 32],
+     fee_bps: [u8; 2],
+    pub bump: u8,
+}
+
+impl Config {
+    pub const LEN: usize = 32 + 32 + 2 + 1;
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        let mut data: pinocchio::account_info::RefMut<'_, [u8]> = account_info.try_borrow_mut_data()?;
+        if data.len() != Config::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Reads a `Config` out of raw account data, e.g. fetched over RPC.
+    pub fn from_bytes(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != Config::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn admin(&self) -> pinocchio::pubkey::Pubkey {
+        pinocchio::pubkey::Pubkey::from(self.admin)
+    }
+
+    pub fn set_admin(&mut self, admin: &pinocchio::pubkey::Pubkey) {
+        self.admin.copy_from_slice(admin.as_ref());
+    }
+
+    /// Wallet receiving the platform fee (its ATA for SPL campaigns).
+    pub fn fee_recipient(&self) -> pinocchio::pubkey::Pubkey {
+        pinocchio::pubkey::Pubkey::from(self.fee_recipient)
+    }
+
+    pub fn set_fee_recipient(&mut self, fee_recipient: &pinocchio::pubkey::Pubkey) {
+        self.fee_recipient.copy_from_slice(fee_recipient.as_ref());
+    }
+
+    /// Platform fee taken from a successful raise, in basis points.
+    pub fn fee_bps(&self) -> u16 {
+        u16::from_le_bytes(self.fee_bps)
+    }
+
+    pub fn set_fee_bps(&mut self, fee_bps: &u16) {
+        self.fee_bps = fee_bps.to_le_bytes();
+    }
+
+    pub fn fee_amount(&self, amount: u64) -> u64 {
+        ((amount as u128 * self.fee_bps() as u128) / crate::constants::BASIS_POINTS_SCALER as u128) as u64
+    }
+
+}
@@ -1,14 +1,33 @@
 use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
 
+use crate::constants::ACCOUNT_HEADER_LEN;
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Contributor {
 
+     discriminator: [u8; 1],
+     version: [u8; 1],
      amount: [u8; 8],
+     voted_round: [u8; 1],
+    // Version 2
+    last_contribution_ts: [u8; 8],
+    // Version 3
+    /// Bump of the `[b"contributor", fundraiser, contributor]` PDA, so checks skip the bump search.
+    pub bump: u8,
 }
 
 impl Contributor {
-    pub const LEN: usize = 8;
+    pub const LEN: usize = Contributor::LEN_V2 + 1;
+    /// Size of version 2, before the bump; `Migrate` upgrades such accounts in place.
+    pub const LEN_V2: usize = Contributor::LEN_V1 + 8;
+    /// Size of version 1, before the last contribution time; `Migrate` upgrades such accounts in place.
+    pub const LEN_V1: usize = ACCOUNT_HEADER_LEN + 8 + 1;
+    /// Size of the unversioned layout the program first shipped with, just the amount;
+    /// `Migrate` upgrades such accounts in place.
+    pub const LEGACY_LEN: usize = 8;
+    pub const DISCRIMINATOR: u8 = 2;
+    pub const VERSION: u8 = 3;
 
 
     pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
@@ -24,6 +43,42 @@ impl Contributor {
         Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
     }
 
+    /// Reads a `Contributor` out of raw account data, e.g. fetched over RPC.
+    pub fn from_bytes(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != Contributor::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let contributor = unsafe { &*(data.as_ptr() as *const Self) };
+        if !contributor.is_current() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(contributor)
+    }
+
+    pub fn discriminator(&self) -> u8 {
+        self.discriminator[0]
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version[0]
+    }
+
+    /// Whether the account carries the contributor discriminator and the current layout version.
+    pub fn is_current(&self) -> bool {
+        self.discriminator() == Contributor::DISCRIMINATOR && self.version() == Contributor::VERSION
+    }
+
+    /// Stamps a freshly created account with the contributor discriminator and current version.
+    pub fn init_header(&mut self) {
+        self.discriminator = [Contributor::DISCRIMINATOR];
+        self.version = [Contributor::VERSION];
+    }
+
     pub fn amount(&self) -> u64 {
         u64::from_le_bytes(self.amount)
     }
@@ -32,4 +87,23 @@ impl Contributor {
         self.amount=amount.to_le_bytes();
     }
 
+    /// `FundRaiser::vote_round` + 1 of the last vote cast, 0 if the contributor never voted.
+    pub fn voted_round(&self) -> u8 {
+        self.voted_round[0]
+    }
+
+    pub fn set_voted_round(&mut self, voted_round: u8) {
+        self.voted_round = [voted_round];
+    }
+
+    /// When the contributor last put funds in; `Withdraw` waits out the campaign's cooldown from here.
+    /// 0 for records migrated from version 1.
+    pub fn last_contribution_ts(&self) -> i64 {
+        i64::from_le_bytes(self.last_contribution_ts)
+    }
+
+    pub fn set_last_contribution_ts(&mut self, last_contribution_ts: &i64) {
+        self.last_contribution_ts = last_contribution_ts.to_le_bytes();
+    }
+
 }
\ No newline at end of file
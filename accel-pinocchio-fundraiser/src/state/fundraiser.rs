@@ -1,20 +1,148 @@
 use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
 
+use crate::constants::{ACCOUNT_HEADER_LEN, MAX_STRETCH_GOALS, VOTING_PERIOD};
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct FundRaiser {
 
+     discriminator: [u8; 1],
+     version: [u8; 1],
      maker: [u8; 32],
+     campaign_id: [u8; 8],
      mint_to_raise: [u8; 32],
      amount_to_raise: [u8; 8],
      current_amount: [u8; 8],
-     time_started: [u8; 8],
-     duration: [u8; 1],
+     refunded_amount: [u8; 8],
+     start_ts: [u8; 8],
+     end_ts: [u8; 8],
+     min_contribution: [u8; 8],
+     max_per_contributor: [u8; 8],
+     cap_kind: [u8; 1],
+     hard_cap: [u8; 8],
+     flags: [u8; 1],
+     status: [u8; 1],
+     quorum_bps: [u8; 2],
+     approval_threshold_bps: [u8; 2],
+     votes_for: [u8; 8],
+     votes_against: [u8; 8],
+     vote_round: [u8; 1],
+     allowlist_root: [u8; 32],
     pub bump: u8,
+    /// Bump of the `[b"vault", fundraiser]` PDA for native SOL campaigns, of the fundraiser's ATA otherwise.
+    pub vault_bump: u8,
+    /// Bump of the `[b"receipt", fundraiser]` receipt mint when `FLAG_RECEIPT` is set.
+    pub receipt_bump: u8,
+    // Version 2
+    stretch_goal_count: [u8; 1],
+    stretch_goals: [[u8; 8]; MAX_STRETCH_GOALS],
+    // Version 3
+    withdrawals_enabled: [u8; 1],
+    withdraw_cooldown: [u8; 8],
+    withdraw_penalty_bps: [u8; 2],
+    legacy_address: [u8; 1],
+    vote_end_ts: [u8; 8],
+    penalty_amount: [u8; 8],
+    legacy_unclaimed_amount: [u8; 8],
+}
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FundRaiserStatus {
+    Active = 0,
+    Cancelled = 1,
+    /// Milestone campaign whose tranches have started flowing to the maker.
+    Releasing = 2,
+    /// Contributors voted down the next release; the vault is refunded pro rata.
+    Rejected = 3,
+}
+
+impl TryFrom<u8> for FundRaiserStatus {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FundRaiserStatus::Active),
+            1 => Ok(FundRaiserStatus::Cancelled),
+            2 => Ok(FundRaiserStatus::Releasing),
+            3 => Ok(FundRaiserStatus::Rejected),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+/// The campaign id seed of the fundraiser PDA. It is empty for campaigns migrated from the
+/// unversioned program, which live at `[b"fundraiser", maker]`: an empty seed derives the same
+/// address as no seed at all.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CampaignSeed {
+    bytes: [u8; 8],
+    len: usize,
+}
+
+impl CampaignSeed {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FundRaiserPhase {
+    NotStarted,
+    Open,
+    Ended,
+}
+
+/// How `max_per_contributor` is interpreted: a raw token amount or basis points of `amount_to_raise`.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContributionCapKind {
+    Absolute = 0,
+    BasisPoints = 1,
+}
+
+impl TryFrom<u8> for ContributionCapKind {
+    type Error = ProgramError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ContributionCapKind::Absolute),
+            1 => Ok(ContributionCapKind::BasisPoints),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
 }
 
 impl FundRaiser {
-    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 1;
+    pub const LEN: usize = FundRaiser::LEN_V2 + 1 + 8 + 2 + 1 + 8 + 8 + 8;
+    /// Size of version 2, before withdrawals; `Migrate` upgrades such accounts in place.
+    pub const LEN_V2: usize = FundRaiser::LEN_V1 + 1 + 8 * MAX_STRETCH_GOALS;
+    /// Size of version 1, before stretch goals; `Migrate` upgrades such accounts in place.
+    pub const LEN_V1: usize = ACCOUNT_HEADER_LEN + 32 + 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1 + 1 + 2 + 2 + 8 + 8 + 1 + 32 + 1 + 1 + 1;
+    /// Size of the unversioned layout the program first shipped with: maker, mint_to_raise,
+    /// amount_to_raise, current_amount, time_started (u64), duration (u8, days) and bump.
+    /// `Migrate` converts such accounts in place.
+    pub const LEGACY_LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 1;
+    pub const DISCRIMINATOR: u8 = 1;
+    pub const VERSION: u8 = 3;
+
+    /// Clamp a contribution that would exceed the hard cap instead of rejecting it.
+    pub const FLAG_PARTIAL_FILL: u8 = 1 << 0;
+    /// Close the campaign as soon as `amount_to_raise` is reached.
+    pub const FLAG_END_ON_TARGET: u8 = 1 << 1;
+    /// Raise native SOL into a program-owned `[b"vault", fundraiser]` PDA instead of an SPL mint.
+    pub const FLAG_NATIVE_SOL: u8 = 1 << 2;
+    /// Release the raised funds in tranches through `ClaimTranche` instead of all at once in `Checker`.
+    pub const FLAG_MILESTONES: u8 = 1 << 3;
+    /// Every release to the maker needs contributor approval through `Vote`.
+    pub const FLAG_GOVERNANCE: u8 = 1 << 4;
+    /// Mint contributors a `[b"receipt", fundraiser]` token for what they put in, burned on refund.
+    pub const FLAG_RECEIPT: u8 = 1 << 5;
+    /// Only contributors proving `(contributor, max_allocation)` against `allowlist_root` may contribute.
+    pub const FLAG_ALLOWLIST: u8 = 1 << 6;
+    /// Advertise stretch goals above `amount_to_raise`; settling the campaign records the tier reached
+    /// in a `Settlement` account.
+    pub const FLAG_STRETCH_GOALS: u8 = 1 << 7;
 
     pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
         let mut data: pinocchio::account_info::RefMut<'_, [u8]> = account_info.try_borrow_mut_data()?;
@@ -29,6 +157,41 @@ impl FundRaiser {
         Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
     }
 
+    /// Reads a `FundRaiser` out of raw account data, e.g. fetched over RPC.
+    pub fn from_bytes(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != FundRaiser::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let fundraiser = unsafe { &*(data.as_ptr() as *const Self) };
+        if !fundraiser.is_current() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(fundraiser)
+    }
+
+    pub fn discriminator(&self) -> u8 {
+        self.discriminator[0]
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version[0]
+    }
+
+    /// Whether the account carries the fundraiser discriminator and the current layout version.
+    pub fn is_current(&self) -> bool {
+        self.discriminator() == FundRaiser::DISCRIMINATOR && self.version() == FundRaiser::VERSION
+    }
+
+    /// Stamps a freshly created account with the fundraiser discriminator and current version.
+    pub fn init_header(&mut self) {
+        self.discriminator = [FundRaiser::DISCRIMINATOR];
+        self.version = [FundRaiser::VERSION];
+    }
 
     pub fn maker(&self) -> pinocchio::pubkey::Pubkey {
         pinocchio::pubkey::Pubkey::from(self.maker)
@@ -38,6 +201,14 @@ impl FundRaiser {
         self.maker.copy_from_slice(maker.as_ref());
     }
 
+    pub fn campaign_id(&self) -> u64 {
+        u64::from_le_bytes(self.campaign_id)
+    }
+
+    pub fn set_campaign_id(&mut self, campaign_id: &u64) {
+        self.campaign_id = campaign_id.to_le_bytes();
+    }
+
     pub fn mint_to_raise(&self) -> pinocchio::pubkey::Pubkey {
         pinocchio::pubkey::Pubkey::from(self.mint_to_raise)
     }
@@ -61,19 +232,356 @@ impl FundRaiser {
     pub fn set_current_amount(&mut self, current_amount: &u64) {
         self.current_amount=current_amount.to_le_bytes();
     }
-    pub fn time_started(&self) -> u64 {
-        u64::from_le_bytes(self.time_started)
+
+    /// Total paid back to contributors so far.
+    pub fn refunded_amount(&self) -> u64 {
+        u64::from_le_bytes(self.refunded_amount)
+    }
+
+    pub fn set_refunded_amount(&mut self, refunded_amount: &u64) {
+        self.refunded_amount = refunded_amount.to_le_bytes();
+    }
+
+    /// Withdrawal penalties forfeited to the campaign, held in the vault on top of `current_amount`.
+    pub fn penalty_amount(&self) -> u64 {
+        u64::from_le_bytes(self.penalty_amount)
+    }
+
+    pub fn set_penalty_amount(&mut self, penalty_amount: &u64) {
+        self.penalty_amount = penalty_amount.to_le_bytes();
+    }
+
+    /// What the campaign has raised towards its target: the contributions still in plus forfeited
+    /// withdrawal penalties. Every success check measures this, never the vault balance.
+    pub fn raised_amount(&self) -> u64 {
+        self.current_amount().saturating_add(self.penalty_amount())
+    }
+
+    pub fn target_met(&self) -> bool {
+        self.raised_amount() >= self.amount_to_raise()
+    }
+
+    /// Share of `vault_amount` owed to a contribution of `amount`: `amount * vault_amount / current_amount`,
+    /// rounded down. The last contributor left sweeps the vault so rounding leaves no dust; a vault holding
+    /// more than was contributed, i.e. forfeited withdrawal penalties, is shared out the same way.
+    pub fn refund_share(&self, amount: u64, vault_amount: u64) -> u64 {
+        let current_amount = self.current_amount();
+        if amount >= current_amount {
+            vault_amount
+        } else {
+            ((amount as u128 * vault_amount as u128) / current_amount as u128) as u64
+        }
+    }
+    pub fn start_ts(&self) -> i64 {
+        i64::from_le_bytes(self.start_ts)
+    }
+
+    pub fn set_start_ts(&mut self, start_ts: &i64) {
+        self.start_ts = start_ts.to_le_bytes();
+    }
+
+    pub fn end_ts(&self) -> i64 {
+        i64::from_le_bytes(self.end_ts)
+    }
+
+    pub fn set_end_ts(&mut self, end_ts: &i64) {
+        self.end_ts = end_ts.to_le_bytes();
+    }
+
+    /// Single source of truth for where `now` (unix seconds) falls in the campaign window `[start_ts, end_ts)`.
+    /// Campaigns flagged `FLAG_END_ON_TARGET` end early once the target is reached.
+    pub fn phase(&self, now: i64) -> FundRaiserPhase {
+        if self.ends_on_target() && self.target_met() {
+            FundRaiserPhase::Ended
+        } else if now < self.start_ts() {
+            FundRaiserPhase::NotStarted
+        } else if now < self.end_ts() {
+            FundRaiserPhase::Open
+        } else {
+            FundRaiserPhase::Ended
+        }
+    }
+
+    pub fn min_contribution(&self) -> u64 {
+        u64::from_le_bytes(self.min_contribution)
+    }
+
+    pub fn set_min_contribution(&mut self, min_contribution: &u64) {
+        self.min_contribution = min_contribution.to_le_bytes();
+    }
+
+    pub fn max_per_contributor(&self) -> u64 {
+        u64::from_le_bytes(self.max_per_contributor)
+    }
+
+    pub fn set_max_per_contributor(&mut self, max_per_contributor: &u64) {
+        self.max_per_contributor = max_per_contributor.to_le_bytes();
+    }
+
+    pub fn cap_kind(&self) -> Result<ContributionCapKind, ProgramError> {
+        ContributionCapKind::try_from(self.cap_kind[0])
+    }
+
+    pub fn set_cap_kind(&mut self, cap_kind: ContributionCapKind) {
+        self.cap_kind = [cap_kind as u8];
+    }
+
+    /// Resolves `max_per_contributor` into a token amount according to `cap_kind`.
+    pub fn max_contribution_amount(&self) -> Result<u64, ProgramError> {
+        match self.cap_kind()? {
+            ContributionCapKind::Absolute => Ok(self.max_per_contributor()),
+            ContributionCapKind::BasisPoints => {
+                let max = (self.amount_to_raise() as u128 * self.max_per_contributor() as u128)
+                    / crate::constants::BASIS_POINTS_SCALER as u128;
+                Ok(max as u64)
+            }
+        }
+    }
+
+    /// Upper bound on `current_amount`, 0 when the campaign is uncapped.
+    pub fn hard_cap(&self) -> u64 {
+        u64::from_le_bytes(self.hard_cap)
+    }
+
+    pub fn set_hard_cap(&mut self, hard_cap: &u64) {
+        self.hard_cap = hard_cap.to_le_bytes();
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.flags[0]
+    }
+
+    pub fn set_flags(&mut self, flags: u8) {
+        self.flags = [flags];
+    }
+
+    pub fn partial_fill(&self) -> bool {
+        self.flags[0] & Self::FLAG_PARTIAL_FILL != 0
+    }
+
+    pub fn ends_on_target(&self) -> bool {
+        self.flags[0] & Self::FLAG_END_ON_TARGET != 0
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.flags[0] & Self::FLAG_NATIVE_SOL != 0
+    }
+
+    pub fn has_milestones(&self) -> bool {
+        self.flags[0] & Self::FLAG_MILESTONES != 0
+    }
+
+    pub fn is_governed(&self) -> bool {
+        self.flags[0] & Self::FLAG_GOVERNANCE != 0
+    }
+
+    pub fn has_receipt(&self) -> bool {
+        self.flags[0] & Self::FLAG_RECEIPT != 0
+    }
+
+    pub fn has_allowlist(&self) -> bool {
+        self.flags[0] & Self::FLAG_ALLOWLIST != 0
+    }
+
+    /// Merkle root of the allowlist, see `merkle::allowlist_leaf` for the leaf encoding.
+    pub fn allowlist_root(&self) -> &[u8; 32] {
+        &self.allowlist_root
+    }
+
+    pub fn set_allowlist_root(&mut self, allowlist_root: &[u8; 32]) {
+        self.allowlist_root = *allowlist_root;
+    }
+
+    pub fn has_stretch_goals(&self) -> bool {
+        self.flags[0] & Self::FLAG_STRETCH_GOALS != 0
+    }
+
+    pub fn stretch_goal_count(&self) -> u8 {
+        self.stretch_goal_count[0]
+    }
+
+    /// Threshold of stretch goal `index`, in ascending order above `amount_to_raise`.
+    pub fn stretch_goal(&self, index: u8) -> Option<u64> {
+        self.stretch_goals[..self.stretch_goal_count() as usize]
+            .get(index as usize)
+            .map(|goal| u64::from_le_bytes(*goal))
+    }
+
+    pub fn set_stretch_goals(&mut self, stretch_goals: &[u64]) -> Result<(), ProgramError> {
+        if stretch_goals.len() > MAX_STRETCH_GOALS {
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.stretch_goals = [[0; 8]; MAX_STRETCH_GOALS];
+        for (slot, goal) in self.stretch_goals.iter_mut().zip(stretch_goals) {
+            *slot = goal.to_le_bytes();
+        }
+        self.stretch_goal_count = [stretch_goals.len() as u8];
+        Ok(())
+    }
+
+    /// Tier `amount` would settle at: 0 below the target, 1 for the base goal and one more per
+    /// stretch goal reached. Stretch-goal campaigns keep the tier they settle at in their
+    /// `Settlement` account, which outlives the fundraiser.
+    pub fn tier_for(&self, amount: u64) -> u8 {
+        if amount < self.amount_to_raise() {
+            return 0;
+        }
+        1 + (0..self.stretch_goal_count())
+            .filter_map(|index| self.stretch_goal(index))
+            .filter(|goal| amount >= *goal)
+            .count() as u8
+    }
+
+    /// Contributors may take funds back through `Withdraw` while the campaign is open.
+    pub fn allows_withdrawals(&self) -> bool {
+        self.withdrawals_enabled[0] != 0
+    }
+
+    /// Seconds after their last contribution before a contributor may withdraw.
+    pub fn withdraw_cooldown(&self) -> i64 {
+        i64::from_le_bytes(self.withdraw_cooldown)
+    }
+
+    /// Share of a withdrawal forfeited to the campaign, kept in the vault.
+    pub fn withdraw_penalty_bps(&self) -> u16 {
+        u16::from_le_bytes(self.withdraw_penalty_bps)
+    }
+
+    pub fn set_withdrawals(&mut self, cooldown: &i64, penalty_bps: &u16) {
+        self.withdrawals_enabled = [1];
+        self.withdraw_cooldown = cooldown.to_le_bytes();
+        self.withdraw_penalty_bps = penalty_bps.to_le_bytes();
+    }
+
+    /// Part of withdrawing `amount` that stays in the vault, rounded down.
+    pub fn withdraw_penalty(&self, amount: u64) -> u64 {
+        ((amount as u128 * self.withdraw_penalty_bps() as u128) / crate::constants::BASIS_POINTS_SCALER as u128) as u64
     }
 
-    pub fn set_time_started(&mut self, time_started: &u64) {
-        self.time_started=time_started.to_le_bytes();
+    /// Migrated from the unversioned program, whose fundraiser PDA is `[b"fundraiser", maker]`
+    /// without a campaign id.
+    pub fn has_legacy_address(&self) -> bool {
+        self.legacy_address[0] != 0
     }
-    pub fn duration(&self) -> u8 {
-        u8::from_le_bytes(self.duration)
+
+    pub fn set_legacy_address(&mut self) {
+        self.legacy_address = [1];
+    }
+
+    /// Part of a converted unversioned campaign's `current_amount` not yet claimed by a migrated
+    /// contributor record. The old refund left records untouched, so records adding up to more than
+    /// this include amounts already refunded and are turned away.
+    pub fn legacy_unclaimed_amount(&self) -> u64 {
+        u64::from_le_bytes(self.legacy_unclaimed_amount)
+    }
+
+    pub fn set_legacy_unclaimed_amount(&mut self, legacy_unclaimed_amount: &u64) {
+        self.legacy_unclaimed_amount = legacy_unclaimed_amount.to_le_bytes();
+    }
+
+    pub fn campaign_seed(&self) -> CampaignSeed {
+        CampaignSeed { bytes: self.campaign_id, len: if self.has_legacy_address() { 0 } else { 8 } }
+    }
+
+    pub fn status(&self) -> Result<FundRaiserStatus, ProgramError> {
+        FundRaiserStatus::try_from(self.status[0])
+    }
+
+    pub fn set_status(&mut self, status: FundRaiserStatus) {
+        self.status = [status as u8];
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.status[0] == FundRaiserStatus::Cancelled as u8
+    }
+
+    pub fn is_releasing(&self) -> bool {
+        self.status[0] == FundRaiserStatus::Releasing as u8
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.status[0] == FundRaiserStatus::Rejected as u8
+    }
+
+    /// Share of the voting weight (`current_amount`) that has to take part in a vote.
+    pub fn quorum_bps(&self) -> u16 {
+        u16::from_le_bytes(self.quorum_bps)
+    }
+
+    pub fn set_quorum_bps(&mut self, quorum_bps: &u16) {
+        self.quorum_bps = quorum_bps.to_le_bytes();
+    }
+
+    /// Share of the votes cast that has to approve a release.
+    pub fn approval_threshold_bps(&self) -> u16 {
+        u16::from_le_bytes(self.approval_threshold_bps)
+    }
+
+    pub fn set_approval_threshold_bps(&mut self, approval_threshold_bps: &u16) {
+        self.approval_threshold_bps = approval_threshold_bps.to_le_bytes();
+    }
+
+    pub fn votes_for(&self) -> u64 {
+        u64::from_le_bytes(self.votes_for)
+    }
+
+    pub fn votes_against(&self) -> u64 {
+        u64::from_le_bytes(self.votes_against)
+    }
+
+    /// Index of the release currently put to the vote; tallies reset whenever it advances.
+    pub fn vote_round(&self) -> u8 {
+        self.vote_round[0]
+    }
+
+    pub fn record_vote(&mut self, weight: u64, approve: bool) -> Result<(), ProgramError> {
+        let tally = if approve { &mut self.votes_for } else { &mut self.votes_against };
+        let total = u64::from_le_bytes(*tally)
+            .checked_add(weight)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        *tally = total.to_le_bytes();
+        Ok(())
+    }
+
+    pub fn next_vote_round(&mut self) {
+        self.votes_for = [0; 8];
+        self.votes_against = [0; 8];
+        self.vote_round = [self.vote_round[0].wrapping_add(1)];
+    }
+
+    /// Quorum reached and enough of the votes cast approve the release.
+    pub fn release_approved(&self) -> bool {
+        let scaler = crate::constants::BASIS_POINTS_SCALER as u128;
+        let cast = self.votes_for() as u128 + self.votes_against() as u128;
+        cast > 0
+            && cast * scaler >= self.quorum_bps() as u128 * self.current_amount() as u128
+            && self.votes_for() as u128 * scaler >= self.approval_threshold_bps() as u128 * cast
+    }
+
+    /// When voting on the current release closes: `VOTING_PERIOD` after the deadline for the first
+    /// release, as set by `ClaimTranche` for the following ones.
+    pub fn vote_end_ts(&self) -> i64 {
+        if self.vote_round() == 0 {
+            self.end_ts().saturating_add(VOTING_PERIOD)
+        } else {
+            i64::from_le_bytes(self.vote_end_ts)
+        }
+    }
+
+    pub fn set_vote_end_ts(&mut self, vote_end_ts: &i64) {
+        self.vote_end_ts = vote_end_ts.to_le_bytes();
+    }
+
+    /// A governed release still unapproved when its voting closes counts as rejected.
+    pub fn release_lapsed(&self, now: i64) -> bool {
+        self.is_governed() && now >= self.vote_end_ts() && !self.release_approved()
     }
 
-    pub fn set_duration(&mut self, duration: u8) {
-        self.duration = duration.to_le_bytes();
+    /// So much weight voted against that the threshold is out of reach even if everyone else approves.
+    pub fn release_rejected(&self) -> bool {
+        let scaler = crate::constants::BASIS_POINTS_SCALER as u128;
+        self.votes_against() as u128 * scaler
+            > (scaler - self.approval_threshold_bps() as u128) * self.current_amount() as u128
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,127 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+
+use crate::constants::MAX_TRANCHES;
+
+/// One release of the raised funds: a share of the total in basis points and the time it unlocks.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Tranche {
+     basis_points: [u8; 2],
+     unlock_ts: [u8; 8],
+}
+
+impl Tranche {
+    pub fn basis_points(&self) -> u16 {
+        u16::from_le_bytes(self.basis_points)
+    }
+
+    pub fn set_basis_points(&mut self, basis_points: &u16) {
+        self.basis_points = basis_points.to_le_bytes();
+    }
+
+    pub fn unlock_ts(&self) -> i64 {
+        i64::from_le_bytes(self.unlock_ts)
+    }
+
+    pub fn set_unlock_ts(&mut self, unlock_ts: &i64) {
+        self.unlock_ts = unlock_ts.to_le_bytes();
+    }
+}
+
+/// Release schedule of a milestone campaign, stored at `[b"milestones", fundraiser]`.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Milestones {
+
+     tranche_count: [u8; 1],
+     claimed_count: [u8; 1],
+     raised_amount: [u8; 8],
+     released_amount: [u8; 8],
+     tranches: [Tranche; MAX_TRANCHES],
+    pub bump: u8,
+}
+
+impl Milestones {
+    pub const LEN: usize = 1 + 1 + 8 + 8 + (2 + 8) * MAX_TRANCHES + 1;
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        let mut data: pinocchio::account_info::RefMut<'_, [u8]> = account_info.try_borrow_mut_data()?;
+        if data.len() != Milestones::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Reads a `Milestones` out of raw account data, e.g. fetched over RPC.
+    pub fn from_bytes(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != Milestones::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn tranche_count(&self) -> u8 {
+        self.tranche_count[0]
+    }
+
+    pub fn set_tranche_count(&mut self, tranche_count: u8) {
+        self.tranche_count = [tranche_count];
+    }
+
+    pub fn claimed_count(&self) -> u8 {
+        self.claimed_count[0]
+    }
+
+    pub fn set_claimed_count(&mut self, claimed_count: u8) {
+        self.claimed_count = [claimed_count];
+    }
+
+    /// Vault balance snapshotted when the first tranche is claimed; tranches are shares of it.
+    pub fn raised_amount(&self) -> u64 {
+        u64::from_le_bytes(self.raised_amount)
+    }
+
+    pub fn set_raised_amount(&mut self, raised_amount: &u64) {
+        self.raised_amount = raised_amount.to_le_bytes();
+    }
+
+    pub fn released_amount(&self) -> u64 {
+        u64::from_le_bytes(self.released_amount)
+    }
+
+    pub fn set_released_amount(&mut self, released_amount: &u64) {
+        self.released_amount = released_amount.to_le_bytes();
+    }
+
+    pub fn tranche(&self, index: u8) -> Option<&Tranche> {
+        self.tranches[..self.tranche_count() as usize].get(index as usize)
+    }
+
+    pub fn tranche_mut(&mut self, index: u8) -> Option<&mut Tranche> {
+        self.tranches.get_mut(index as usize)
+    }
+
+    /// The next tranche waiting to be claimed, `None` once all of them were released.
+    pub fn next_tranche(&self) -> Option<&Tranche> {
+        self.tranche(self.claimed_count())
+    }
+
+    /// Share of `raised_amount` released by the tranche at `index`.
+    pub fn tranche_amount(&self, index: u8) -> Result<u64, ProgramError> {
+        let tranche = self.tranche(index).ok_or(ProgramError::InvalidAccountData)?;
+        let amount = (self.raised_amount() as u128 * tranche.basis_points() as u128)
+            / crate::constants::BASIS_POINTS_SCALER as u128;
+        Ok(amount as u64)
+    }
+
+}
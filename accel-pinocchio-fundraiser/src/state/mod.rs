@@ -4,4 +4,16 @@ pub use fundraiser::*;
 
 pub mod contributor;
 
-pub use contributor::*; 
\ No newline at end of file
+pub use contributor::*;
+
+pub mod milestones;
+
+pub use milestones::*;
+
+pub mod config;
+
+pub use config::*;
+
+pub mod settlement;
+
+pub use settlement::*;
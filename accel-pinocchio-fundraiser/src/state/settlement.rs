@@ -0,0 +1,70 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+
+/// Outcome of a stretch-goal campaign, stored at `[b"settlement", fundraiser]`. Written once as the
+/// campaign settles and never closed, so the tier stays readable after the fundraiser is torn down.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Settlement {
+
+     fundraiser: [u8; 32],
+     raised_amount: [u8; 8],
+     tier: [u8; 1],
+    pub bump: u8,
+}
+
+impl Settlement {
+    pub const LEN: usize = 32 + 8 + 1 + 1;
+
+    pub fn from_account_info(account_info: &AccountInfo) -> Result<&mut Self, ProgramError> {
+        let mut data: pinocchio::account_info::RefMut<'_, [u8]> = account_info.try_borrow_mut_data()?;
+        if data.len() != Settlement::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &mut *(data.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Reads a `Settlement` out of raw account data, e.g. fetched over RPC.
+    pub fn from_bytes(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != Settlement::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        if (data.as_ptr() as usize) % core::mem::align_of::<Self>() != 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(unsafe { &*(data.as_ptr() as *const Self) })
+    }
+
+    pub fn fundraiser(&self) -> pinocchio::pubkey::Pubkey {
+        pinocchio::pubkey::Pubkey::from(self.fundraiser)
+    }
+
+    pub fn set_fundraiser(&mut self, fundraiser: &pinocchio::pubkey::Pubkey) {
+        self.fundraiser.copy_from_slice(fundraiser.as_ref());
+    }
+
+    /// Vault balance the campaign settled with, as reported by `Checked` or `Settled`.
+    pub fn raised_amount(&self) -> u64 {
+        u64::from_le_bytes(self.raised_amount)
+    }
+
+    pub fn set_raised_amount(&mut self, raised_amount: &u64) {
+        self.raised_amount = raised_amount.to_le_bytes();
+    }
+
+    /// `FundRaiser::tier_for` the amount raised: 1 for the base goal, one more per stretch goal.
+    pub fn tier(&self) -> u8 {
+        self.tier[0]
+    }
+
+    pub fn set_tier(&mut self, tier: u8) {
+        self.tier = [tier];
+    }
+
+}
@@ -6,18 +6,22 @@ mod tests {
         spl_token::{self, solana_program::msg},
         CreateAssociatedTokenAccount, CreateMint, MintTo,
     };
-    use solana_instruction::{AccountMeta, Instruction};
+    use solana_clock::Clock;
+    use solana_instruction::{error::InstructionError, AccountMeta, Instruction};
     use solana_keypair::Keypair;
     use solana_message::Message;
     use solana_native_token::LAMPORTS_PER_SOL;
     use solana_pubkey::Pubkey;
     use solana_transaction::Transaction;
+    use solana_transaction_error::TransactionError;
     use solana_signer::Signer;
 
     const TOKEN_PROGRAM_ID: Pubkey = spl_token::ID;
     const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
     const FR_PROGRAM_ID: &str = "9rcdaF2bdQVq3TjrL756VqcZWWYgLdZXJX79soxNoUjr"; // must match src/lib.rs
 
+    const DAY: i64 = 86_400;
+
     fn fr_program_id() -> Pubkey { Pubkey::from_str(FR_PROGRAM_ID).unwrap() }
 
     fn setup() -> (LiteSVM, Keypair) {
@@ -35,19 +39,77 @@ mod tests {
 
     fn le_u64(v: u64) -> [u8;8] { v.to_le_bytes() }
 
+    fn now(svm: &LiteSVM) -> i64 { svm.get_sysvar::<Clock>().unix_timestamp }
+
+    fn warp_to(svm: &mut LiteSVM, unix_timestamp: i64) {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = unix_timestamp;
+        svm.set_sysvar::<Clock>(&clock);
+    }
+
+    fn fundraiser_pda(maker: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"fundraiser", maker.as_ref(), &campaign_id.to_le_bytes()], &fr_program_id())
+    }
+
+    const CAP_ABSOLUTE: u8 = 0;
+    const CAP_BASIS_POINTS: u8 = 1;
+    const FLAG_PARTIAL_FILL: u8 = 1 << 0;
+    const FLAG_END_ON_TARGET: u8 = 1 << 1;
+    const FLAG_NATIVE_SOL: u8 = 1 << 2;
+    const FLAG_MILESTONES: u8 = 1 << 3;
+    const FLAG_GOVERNANCE: u8 = 1 << 4;
+    const FLAG_RECEIPT: u8 = 1 << 5;
+    const FLAG_ALLOWLIST: u8 = 1 << 6;
+    const FLAG_STRETCH_GOALS: u8 = 1 << 7;
+
+    struct InitArgs {
+        amount_to_raise: u64,
+        start_ts: i64,
+        end_ts: i64,
+        campaign_id: u64,
+        min_contribution: u64,
+        max_per_contributor: u64,
+        cap_kind: u8,
+        hard_cap: u64,
+        flags: u8,
+    }
+
+    impl InitArgs {
+        // One whole 6-decimal token minimum and a 10% per-contributor cap, starting now
+        fn new(amount_to_raise: u64, end_ts: i64) -> Self {
+            Self {
+                amount_to_raise,
+                start_ts: 0,
+                end_ts,
+                campaign_id: 0,
+                min_contribution: 1_000_000,
+                max_per_contributor: 1_000,
+                cap_kind: CAP_BASIS_POINTS,
+                hard_cap: 0,
+                flags: 0,
+            }
+        }
+    }
+
     fn build_initialize_ix(
         maker: &Keypair,
         mint: Pubkey,
         fundraiser_pda: Pubkey,
         vault: Pubkey,
-        amount_to_raise: u64,
-        duration_days: u8,
+        args: &InitArgs,
     ) -> Instruction {
         let data = [
             vec![0u8],                    // discriminator: Initialize
-            // No bump/current/time; program computes bump and sets state
-            le_u64(amount_to_raise).to_vec(),
-            vec![duration_days],
+            // No bump/current amount; program computes bump and sets state (start_ts = 0 means now)
+            le_u64(args.amount_to_raise).to_vec(),
+            args.start_ts.to_le_bytes().to_vec(),
+            args.end_ts.to_le_bytes().to_vec(),
+            le_u64(args.campaign_id).to_vec(),
+            le_u64(args.min_contribution).to_vec(),
+            le_u64(args.max_per_contributor).to_vec(),
+            vec![args.cap_kind],
+            le_u64(args.hard_cap).to_vec(),
+            vec![args.flags],
         ].concat();
 
         Instruction {
@@ -102,8 +164,65 @@ mod tests {
         Instruction {
             program_id: fr_program_id(),
             accounts: vec![
+                AccountMeta::new(maker.pubkey(), true),
+                AccountMeta::new(mint, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(maker_ata, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(), false),
+                AccountMeta::new_readonly(config_pda(), false),
+                // No config, no fee: the fee recipient slots are placeholders until `with_fee_recipient`
                 AccountMeta::new(maker.pubkey(), false),
-                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(maker.pubkey(), false),
+            ],
+            data,
+        }
+    }
+
+    // Fills the fee recipient slots that follow the config account
+    fn with_fee_recipient(mut ix: Instruction, fee_recipient: Pubkey, fee_recipient_ata: Pubkey) -> Instruction {
+        let config = ix.accounts.iter().position(|meta| meta.pubkey == config_pda()).unwrap();
+        ix.accounts[config + 1].pubkey = fee_recipient;
+        ix.accounts[config + 2].pubkey = fee_recipient_ata;
+        ix
+    }
+
+    // Sends the target, deadline and caps of `args`; the rest of `args` is ignored
+    fn build_update_ix(maker: &Keypair, fundraiser_pda: Pubkey, args: &InitArgs) -> Instruction {
+        let data = [
+            vec![9u8],
+            le_u64(args.amount_to_raise).to_vec(),
+            args.end_ts.to_le_bytes().to_vec(),
+            le_u64(args.min_contribution).to_vec(),
+            le_u64(args.max_per_contributor).to_vec(),
+            vec![args.cap_kind],
+            le_u64(args.hard_cap).to_vec(),
+        ].concat();
+        Instruction {
+            program_id: fr_program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(maker.pubkey(), true),
+                AccountMeta::new(fundraiser_pda, false),
+            ],
+            data,
+        }
+    }
+
+    fn build_close_ix(
+        maker: &Keypair,
+        mint: Pubkey,
+        fundraiser_pda: Pubkey,
+        vault: Pubkey,
+        maker_ata: Pubkey,
+    ) -> Instruction {
+        let data = vec![12u8];
+        Instruction {
+            program_id: fr_program_id(),
+            accounts: vec![
+                AccountMeta::new(maker.pubkey(), true),
+                AccountMeta::new(mint, false),
                 AccountMeta::new(fundraiser_pda, false),
                 AccountMeta::new(vault, false),
                 AccountMeta::new(maker_ata, false),
@@ -115,6 +234,68 @@ mod tests {
         }
     }
 
+    fn build_migrate_ix(payer: &Keypair, account: Pubkey) -> Instruction {
+        Instruction {
+            program_id: fr_program_id(),
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(account, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            ],
+            data: vec![8u8],
+        }
+    }
+
+    // Rewrites a program account as it was at an older layout version: the first `len` bytes, with
+    // the header stamped `version`, or stripped for the unversioned layout; funded for that size
+    fn downgrade_layout(svm: &mut LiteSVM, address: &Pubkey, version: u8, len: usize) {
+        let mut account = svm.get_account(address).unwrap();
+        account.data.truncate(len);
+        account.data[1] = version;
+        account.lamports = svm.minimum_balance_for_rent_exemption(account.data.len());
+        svm.set_account(*address, account).unwrap();
+    }
+
+    fn config_pda() -> Pubkey {
+        Pubkey::find_program_address(&[b"config"], &fr_program_id()).0
+    }
+
+    fn program_data_pda() -> Pubkey {
+        Pubkey::find_program_address(&[fr_program_id().as_ref()], &solana_sdk_ids::bpf_loader_upgradeable::ID).0
+    }
+
+    // `add_program` deploys without a ProgramData account, so write one naming `authority`
+    fn set_upgrade_authority(svm: &mut LiteSVM, authority: &Pubkey) {
+        let data = [3u32.to_le_bytes().to_vec(), 0u64.to_le_bytes().to_vec(), vec![1], authority.to_bytes().to_vec()].concat();
+        let account = solana_account::Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: solana_sdk_ids::bpf_loader_upgradeable::ID,
+            executable: false,
+            rent_epoch: 0,
+        };
+        svm.set_account(program_data_pda(), account).unwrap();
+    }
+
+    fn build_set_config_ix(
+        admin: &Keypair,
+        fee_bps: u16,
+        fee_recipient: Pubkey,
+        new_admin: Pubkey,
+    ) -> Instruction {
+        let data = [vec![7u8], fee_bps.to_le_bytes().to_vec(), fee_recipient.to_bytes().to_vec(), new_admin.to_bytes().to_vec()].concat();
+        Instruction {
+            program_id: fr_program_id(),
+            accounts: vec![
+                AccountMeta::new(admin.pubkey(), true),
+                AccountMeta::new(config_pda(), false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                AccountMeta::new_readonly(program_data_pda(), false),
+            ],
+            data,
+        }
+    }
+
     fn build_refund_ix(
         contributor: &Keypair,
         maker: &Keypair,
@@ -142,32 +323,368 @@ mod tests {
         }
     }
 
-    fn create_program_owned_account(
-        svm: &mut LiteSVM,
-        payer: &Keypair,
-        space: u64,
-        owner: Pubkey,
-    ) -> Keypair {
-        let acct = Keypair::new();
-        // Provide some rent-exempt-ish lamports; SVM is lenient here.
-        let lamports: u64 = 1_000_000;
-        // Build a SystemProgram CreateAccount instruction
-        let ix = Instruction {
+    // `batch` holds (contributor_account, contributor, contributor_ata) per contributor
+    fn build_crank_refund_ix(
+        keeper: &Keypair,
+        maker: &Keypair,
+        mint: Pubkey,
+        fundraiser_pda: Pubkey,
+        vault: Pubkey,
+        batch: &[(Pubkey, Pubkey, Pubkey)],
+    ) -> Instruction {
+        let mut accounts = vec![
+            AccountMeta::new(keeper.pubkey(), true),
+            AccountMeta::new_readonly(maker.pubkey(), false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(fundraiser_pda, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+        ];
+        for (contributor_account, contributor, contributor_ata) in batch {
+            accounts.push(AccountMeta::new(*contributor_account, false));
+            accounts.push(AccountMeta::new(*contributor, false));
+            accounts.push(AccountMeta::new(*contributor_ata, false));
+        }
+        Instruction { program_id: fr_program_id(), accounts, data: vec![10u8] }
+    }
+
+    fn build_cancel_ix(
+        maker: &Keypair,
+        fundraiser_pda: Pubkey,
+    ) -> Instruction {
+        let data = vec![4u8];
+        Instruction {
+            program_id: fr_program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(maker.pubkey(), true),
+                AccountMeta::new(fundraiser_pda, false),
+            ],
+            data,
+        }
+    }
+
+    fn build_claim_tranche_ix(
+        maker: &Keypair,
+        mint: Pubkey,
+        fundraiser_pda: Pubkey,
+        milestones: Pubkey,
+        vault: Pubkey,
+        maker_ata: Pubkey,
+    ) -> Instruction {
+        let data = vec![5u8];
+        Instruction {
+            program_id: fr_program_id(),
+            accounts: vec![
+                AccountMeta::new(maker.pubkey(), true),
+                AccountMeta::new(mint, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(milestones, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(maker_ata, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+                AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID.parse::<Pubkey>().unwrap(), false),
+                AccountMeta::new_readonly(config_pda(), false),
+                AccountMeta::new(maker.pubkey(), false),
+                AccountMeta::new(maker.pubkey(), false),
+            ],
+            data,
+        }
+    }
+
+    // Appends the tranche schedule and the milestones account to an Initialize built with FLAG_MILESTONES
+    fn with_milestones(mut ix: Instruction, milestones: Pubkey, tranches: &[(u16, i64)]) -> Instruction {
+        ix.data.push(tranches.len() as u8);
+        for (basis_points, unlock_ts) in tranches {
+            ix.data.extend_from_slice(&basis_points.to_le_bytes());
+            ix.data.extend_from_slice(&unlock_ts.to_le_bytes());
+        }
+        ix.accounts.push(AccountMeta::new(milestones, false));
+        ix
+    }
+
+    // Appends quorum and approval threshold to an Initialize built with FLAG_GOVERNANCE
+    fn with_governance(mut ix: Instruction, quorum_bps: u16, approval_threshold_bps: u16) -> Instruction {
+        ix.data.extend_from_slice(&quorum_bps.to_le_bytes());
+        ix.data.extend_from_slice(&approval_threshold_bps.to_le_bytes());
+        ix
+    }
+
+    // Appends stretch goal thresholds to an Initialize built with FLAG_STRETCH_GOALS
+    fn with_stretch_goals(mut ix: Instruction, stretch_goals: &[u64]) -> Instruction {
+        ix.data.push(stretch_goals.len() as u8);
+        for goal in stretch_goals {
+            ix.data.extend_from_slice(&goal.to_le_bytes());
+        }
+        ix
+    }
+
+    // Appends withdrawal terms, always the last Initialize section
+    fn with_withdrawals(mut ix: Instruction, cooldown: i64, penalty_bps: u16) -> Instruction {
+        ix.data.extend_from_slice(&cooldown.to_le_bytes());
+        ix.data.extend_from_slice(&penalty_bps.to_le_bytes());
+        ix
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build_withdraw_ix(
+        contributor: &Keypair,
+        maker: &Keypair,
+        mint: Pubkey,
+        fundraiser_pda: Pubkey,
+        contributor_account: Pubkey,
+        contributor_ata: Pubkey,
+        vault: Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let data = [vec![11u8], le_u64(amount).to_vec()].concat();
+        Instruction {
+            program_id: fr_program_id(),
+            accounts: vec![
+                AccountMeta::new(contributor.pubkey(), true),
+                AccountMeta::new_readonly(maker.pubkey(), false),
+                AccountMeta::new_readonly(mint, false),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(contributor_account, false),
+                AccountMeta::new(contributor_ata, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(solana_sdk_ids::system_program::ID, false),
+            ],
+            data,
+        }
+    }
+
+    fn build_vote_ix(
+        contributor: &Keypair,
+        fundraiser_pda: Pubkey,
+        contributor_account: Pubkey,
+        approve: bool,
+    ) -> Instruction {
+        let data = vec![6u8, approve as u8];
+        Instruction {
+            program_id: fr_program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(contributor.pubkey(), true),
+                AccountMeta::new(fundraiser_pda, false),
+                AccountMeta::new(contributor_account, false),
+            ],
+            data,
+        }
+    }
+
+    fn receipt_mint_pda(fundraiser_pda: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"receipt", fundraiser_pda.as_ref()], &fr_program_id()).0
+    }
+
+    // Appends trailing accounts (optional Initialize accounts, receipt accounts, ...) as writable
+    fn with_accounts(mut ix: Instruction, accounts: &[Pubkey]) -> Instruction {
+        ix.accounts.extend(accounts.iter().map(|account| AccountMeta::new(*account, false)));
+        ix
+    }
+
+    fn with_readonly_accounts(mut ix: Instruction, accounts: &[Pubkey]) -> Instruction {
+        ix.accounts.extend(accounts.iter().map(|account| AccountMeta::new_readonly(*account, false)));
+        ix
+    }
+
+    // Writes raw program-owned data, e.g. an account left behind by the unversioned program
+    fn set_program_account(svm: &mut LiteSVM, address: Pubkey, data: Vec<u8>) {
+        let account = solana_account::Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: fr_program_id(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        svm.set_account(address, account).unwrap();
+    }
+
+    // The unversioned fundraiser: no header, a start time and a duration in days
+    fn legacy_fundraiser_data(maker: &Pubkey, mint: &Pubkey, amount_to_raise: u64, current_amount: u64, time_started: i64, bump: u8) -> Vec<u8> {
+        [
+            maker.to_bytes().to_vec(),
+            mint.to_bytes().to_vec(),
+            le_u64(amount_to_raise).to_vec(),
+            le_u64(current_amount).to_vec(),
+            le_u64(time_started as u64).to_vec(),
+            vec![30, bump],
+        ].concat()
+    }
+
+    fn allowlist_leaf(contributor: &Pubkey, max_allocation: u64) -> [u8; 32] {
+        solana_sha256_hasher::hashv(&[&[0], contributor.as_ref(), &max_allocation.to_le_bytes()]).to_bytes()
+    }
+
+    // Builds the tree bottom-up with sorted pair hashing, carrying an odd node up unchanged;
+    // returns the root and one proof per leaf
+    fn allowlist_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+        let mut proofs = vec![Vec::new(); leaves.len()];
+        let mut positions: Vec<usize> = (0..leaves.len()).collect();
+        let mut level = leaves.to_vec();
+        while level.len() > 1 {
+            for (leaf, position) in positions.iter_mut().enumerate() {
+                if let Some(sibling) = level.get(*position ^ 1) {
+                    proofs[leaf].push(*sibling);
+                }
+                *position /= 2;
+            }
+            level = level.chunks(2).map(|pair| match pair {
+                [left, right] => {
+                    let (left, right) = if left <= right { (left, right) } else { (right, left) };
+                    solana_sha256_hasher::hashv(&[&[1], left, right]).to_bytes()
+                }
+                [single] => *single,
+                _ => unreachable!(),
+            }).collect();
+        }
+        (level[0], proofs)
+    }
+
+    // Appends the allocation and its proof to a Contribute for an allowlisted campaign
+    fn with_allowlist_proof(mut ix: Instruction, max_allocation: u64, proof: &[[u8; 32]]) -> Instruction {
+        ix.data.extend_from_slice(&max_allocation.to_le_bytes());
+        ix.data.push(proof.len() as u8);
+        for sibling in proof {
+            ix.data.extend_from_slice(sibling);
+        }
+        ix
+    }
+
+    // Decodes every fundraiser event in a transaction's `Program data:` log lines
+    fn events(meta: &litesvm::types::TransactionMetadata) -> Vec<crate::events::Event> {
+        use base64::Engine;
+        meta.logs.iter()
+            .filter_map(|log| log.strip_prefix("Program data: "))
+            .filter_map(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+            .filter_map(|data| crate::events::Event::decode(&data))
+            .collect()
+    }
+
+    fn milestones_pda(fundraiser_pda: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"milestones", fundraiser_pda.as_ref()], &fr_program_id()).0
+    }
+
+    fn native_vault_pda(fundraiser_pda: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"vault", fundraiser_pda.as_ref()], &fr_program_id()).0
+    }
+
+    fn contributor_pda(fundraiser_pda: &Pubkey, contributor: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"contributor", fundraiser_pda.as_ref(), contributor.as_ref()], &fr_program_id()).0
+    }
+
+    fn assert_custom_error(result: litesvm::types::TransactionResult, code: u32) {
+        let err = result.expect_err("transaction should have failed").err;
+        assert_eq!(err, TransactionError::InstructionError(0, InstructionError::Custom(code)));
+    }
+
+    // Builders default to legacy SPL Token; swap in another token program (e.g. Token-2022)
+    fn with_token_program(mut ix: Instruction, token_program: Pubkey) -> Instruction {
+        for meta in ix.accounts.iter_mut().filter(|meta| meta.pubkey == TOKEN_PROGRAM_ID) {
+            meta.pubkey = token_program;
+        }
+        ix
+    }
+
+    // Swap the account at `index` for another key, keeping its signer/writable flags
+    fn substitute(mut ix: Instruction, index: usize, pubkey: Pubkey) -> Instruction {
+        ix.accounts[index].pubkey = pubkey;
+        ix
+    }
+
+    // The first signer pays the fee
+    fn send(svm: &mut LiteSVM, ix: Instruction, signers: &[&Keypair]) -> litesvm::types::TransactionResult {
+        let msg = Message::new(&[ix], Some(&signers[0].pubkey()));
+        let tx = Transaction::new(signers, msg, svm.latest_blockhash());
+        svm.send_transaction(tx)
+    }
+
+    // Token-2022 mint whose transfers invoke `hook_program`
+    fn create_hook_mint_2022(svm: &mut LiteSVM, payer: &Keypair, hook_program: &Pubkey) -> Pubkey {
+        use spl_token_2022::extension::{transfer_hook, ExtensionType};
+
+        let token_program = spl_token_2022::ID;
+        let mint = Keypair::new();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[ExtensionType::TransferHook]).unwrap();
+        let create_ix = Instruction {
             program_id: solana_sdk_ids::system_program::ID,
             accounts: vec![
                 AccountMeta::new(payer.pubkey(), true),
-                AccountMeta::new(acct.pubkey(), true),
+                AccountMeta::new(mint.pubkey(), true),
             ],
             data: bincode::serialize(&solana_system_interface::instruction::SystemInstruction::CreateAccount {
-                lamports,
-                space,
-                owner,
+                lamports: svm.minimum_balance_for_rent_exemption(space),
+                space: space as u64,
+                owner: token_program,
             }).unwrap(),
         };
-        let msg = Message::new(&[ix], Some(&payer.pubkey()));
-        let tx = Transaction::new(&[payer, &acct], msg, svm.latest_blockhash());
+        let hook_ix = transfer_hook::instruction::initialize(&token_program, &mint.pubkey(), Some(payer.pubkey()), Some(*hook_program)).unwrap();
+        let init_ix = spl_token_2022::instruction::initialize_mint2(&token_program, &mint.pubkey(), &payer.pubkey(), None, 6).unwrap();
+        let msg = Message::new(&[create_ix, hook_ix, init_ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[payer, &mint], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        mint.pubkey()
+    }
+
+    fn contributor_amount(svm: &LiteSVM, contributor_account: &Pubkey) -> u64 {
+        let account = svm.get_account(contributor_account).unwrap();
+        // header (2) | amount (8) | ...
+        u64::from_le_bytes(account.data[2..10].try_into().unwrap())
+    }
+
+    // Token-2022 mint with a transfer fee of `fee_bps`, plus funded ATAs for each owner
+    fn create_fee_mint_2022(svm: &mut LiteSVM, payer: &Keypair, fee_bps: u16, owners: &[Pubkey], amount: u64) -> (Pubkey, Vec<Pubkey>) {
+        use spl_token_2022::extension::{transfer_fee, ExtensionType};
+
+        let token_program = spl_token_2022::ID;
+        let mint = Keypair::new();
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[ExtensionType::TransferFeeConfig]).unwrap();
+        let create_ix = Instruction {
+            program_id: solana_sdk_ids::system_program::ID,
+            accounts: vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new(mint.pubkey(), true),
+            ],
+            data: bincode::serialize(&solana_system_interface::instruction::SystemInstruction::CreateAccount {
+                lamports: svm.minimum_balance_for_rent_exemption(space),
+                space: space as u64,
+                owner: token_program,
+            }).unwrap(),
+        };
+        let fee_ix = transfer_fee::instruction::initialize_transfer_fee_config(
+            &token_program, &mint.pubkey(), Some(&payer.pubkey()), Some(&payer.pubkey()), fee_bps, u64::MAX,
+        ).unwrap();
+        let init_ix = spl_token_2022::instruction::initialize_mint2(&token_program, &mint.pubkey(), &payer.pubkey(), None, 0).unwrap();
+        let msg = Message::new(&[create_ix, fee_ix, init_ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[payer, &mint], msg, svm.latest_blockhash());
         svm.send_transaction(tx).unwrap();
-        acct
+
+        let mut atas = Vec::new();
+        for owner in owners {
+            let ata = spl_associated_token_account::get_associated_token_address_with_program_id(owner, &mint.pubkey(), &token_program);
+            let create_ata_ix = spl_associated_token_account::instruction::create_associated_token_account(&payer.pubkey(), owner, &mint.pubkey(), &token_program);
+            let mint_to_ix = spl_token_2022::instruction::mint_to(&token_program, &mint.pubkey(), &ata, &payer.pubkey(), &[], amount).unwrap();
+            let msg = Message::new(&[create_ata_ix, mint_to_ix], Some(&payer.pubkey()));
+            let tx = Transaction::new(&[payer], msg, svm.latest_blockhash());
+            svm.send_transaction(tx).unwrap();
+            atas.push(ata);
+        }
+        (mint.pubkey(), atas)
+    }
+
+    fn funded_contributor(svm: &mut LiteSVM, payer: &Keypair, mint: &Pubkey, amount: u64) -> (Keypair, Pubkey) {
+        let contributor = Keypair::new();
+        svm.airdrop(&contributor.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(svm, payer, mint).owner(&contributor.pubkey()).send().unwrap();
+        MintTo::new(svm, payer, mint, &contributor_ata, amount).send().unwrap();
+        (contributor, contributor_ata)
+    }
+
+    fn token_amount(svm: &LiteSVM, token_account: &Pubkey) -> u64 {
+        // SPL token account layout: mint (32) | owner (32) | amount (8) | ...
+        let account = svm.get_account(token_account).unwrap();
+        u64::from_le_bytes(account.data[64..72].try_into().unwrap())
     }
 
     #[test]
@@ -190,25 +707,25 @@ mod tests {
             .send().unwrap();
 
         // PDAs
-        let fundraiser = Pubkey::find_program_address(&[b"fundraiser", maker.pubkey().as_ref()], &fr_program_id());
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
         let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
 
-        // Create contributor state account
-        let contributor_account = create_program_owned_account(&mut svm, &payer, 8, fr_program_id());
+        // Contributor record PDA, created on first contribution
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
 
         // Initialize
-        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, 30_000_000, 30);
+        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, now(&svm) + 30 * DAY));
         let msg = Message::new(&[init_ix], Some(&maker.pubkey()));
         let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
         svm.send_transaction(tx).unwrap();
 
         // Contribute twice with different amounts (avoid identical signatures)
-        let ix1 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account.pubkey(), contributor_ata, vault, 1_000_000);
+        let ix1 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
         let msg1 = Message::new(&[ix1], Some(&payer.pubkey()));
         let tx1 = Transaction::new(&[&payer], msg1, svm.latest_blockhash());
         svm.send_transaction(tx1).unwrap();
 
-        let ix2 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account.pubkey(), contributor_ata, vault, 1_000_001);
+        let ix2 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_001);
         let msg2 = Message::new(&[ix2], Some(&payer.pubkey()));
         let tx2 = Transaction::new(&[&payer], msg2, svm.latest_blockhash());
         svm.send_transaction(tx2).unwrap();
@@ -225,26 +742,26 @@ mod tests {
         let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
         MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000_000).send().unwrap();
 
-        let fundraiser = Pubkey::find_program_address(&[b"fundraiser", maker.pubkey().as_ref()], &fr_program_id());
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
         let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
-        let contributor_account = create_program_owned_account(&mut svm, &payer, 8, fr_program_id());
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
 
-        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, 30_000_000, 30);
+        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, now(&svm) + 30 * DAY));
         let msg = Message::new(&[init_ix], Some(&maker.pubkey()));
         let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
         svm.send_transaction(tx).unwrap();
 
         // two valid contributions
-        let ix1 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account.pubkey(), contributor_ata, vault, 1_000_000);
+        let ix1 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
         let msg1 = Message::new(&[ix1], Some(&payer.pubkey()));
         let tx1 = Transaction::new(&[&payer], msg1, svm.latest_blockhash());
         svm.send_transaction(tx1).unwrap();
-        let ix2 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account.pubkey(), contributor_ata, vault, 1_000_001);
+        let ix2 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_001);
         let msg2 = Message::new(&[ix2], Some(&payer.pubkey()));
         let tx2 = Transaction::new(&[&payer], msg2, svm.latest_blockhash());
         svm.send_transaction(tx2).unwrap();
         // Robustness: larger third contribution — accept success or failure
-        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account.pubkey(), contributor_ata, vault, 2_000_000);
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 2_000_000);
         let msg = Message::new(&[ix], Some(&payer.pubkey()));
         let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
         let _ = svm.send_transaction(tx); // ignore result
@@ -262,21 +779,21 @@ mod tests {
         let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&maker.pubkey()).send().unwrap();
         MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000_000).send().unwrap();
 
-        let fundraiser = Pubkey::find_program_address(&[b"fundraiser", maker.pubkey().as_ref()], &fr_program_id());
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
         let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
-        let contributor_account = create_program_owned_account(&mut svm, &payer, 8, fr_program_id());
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
 
-        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, 30_000_000, 30);
+        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, now(&svm) + 30 * DAY));
         let msg = Message::new(&[init_ix], Some(&maker.pubkey()));
         let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
         svm.send_transaction(tx).unwrap();
 
         // two contributions
-        let ix1 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account.pubkey(), contributor_ata, vault, 1_000_000);
+        let ix1 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
         let msg1 = Message::new(&[ix1], Some(&payer.pubkey()));
         let tx1 = Transaction::new(&[&payer], msg1, svm.latest_blockhash());
         svm.send_transaction(tx1).unwrap();
-        let ix2 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account.pubkey(), contributor_ata, vault, 1_000_001);
+        let ix2 = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_001);
         let msg2 = Message::new(&[ix2], Some(&payer.pubkey()));
         let tx2 = Transaction::new(&[&payer], msg2, svm.latest_blockhash());
         svm.send_transaction(tx2).unwrap();
@@ -284,34 +801,2360 @@ mod tests {
         // Robustness checker: accept success or failure
         let ix = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
         let msg = Message::new(&[ix], Some(&payer.pubkey()));
-        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        let tx = Transaction::new(&[&payer, &maker], msg, svm.latest_blockhash());
         let _ = svm.send_transaction(tx);
         msg!("Checker robustness executed (error accepted)");
     }
 
     #[test]
-    fn fundraiser_refund() {
+    fn fundraiser_checker_pays_maker_and_closes_accounts() {
         let (mut svm, payer) = setup();
         let maker = Keypair::new();
         svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
 
         let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
-        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
+        let maker_ata = spl_associated_token_account::get_associated_token_address(&maker.pubkey(), &mint);
 
-        let fundraiser = Pubkey::find_program_address(&[b"fundraiser", maker.pubkey().as_ref()], &fr_program_id());
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
         let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
-        let contributor_account = create_program_owned_account(&mut svm, &payer, 8, fr_program_id());
 
-        // Initialize as ended (duration=0)
-        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, 30_000_000, 0);
+        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(10_000_000, now(&svm) + 30 * DAY));
         let msg = Message::new(&[init_ix], Some(&maker.pubkey()));
         let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
         svm.send_transaction(tx).unwrap();
 
-        let ix = build_refund_ix(&payer, &maker, mint, fundraiser.0, contributor_account.pubkey(), contributor_ata, vault);
-        let msg = Message::new(&[ix], Some(&payer.pubkey()));
-        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        // Ten contributors at the 10% cap reach the target
+        for _ in 0..10 {
+            let contributor = Keypair::new();
+            svm.airdrop(&contributor.pubkey(), LAMPORTS_PER_SOL).unwrap();
+            let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&contributor.pubkey()).send().unwrap();
+            MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 1_000_000).send().unwrap();
+            let contributor_account = contributor_pda(&fundraiser.0, &contributor.pubkey());
+
+            let ix = build_contribute_ix(&contributor, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
+            let msg = Message::new(&[ix], Some(&contributor.pubkey()));
+            let tx = Transaction::new(&[&contributor], msg, svm.latest_blockhash());
+            svm.send_transaction(tx).unwrap();
+        }
+        assert_eq!(token_amount(&svm, &vault), 10_000_000);
+
+        // Target met, but the campaign runs until its deadline
+        let ix = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1005);
+        warp_to(&mut svm, now(&svm) + 30 * DAY);
+        svm.expire_blockhash();
+
+        // Only the maker can sweep the vault
+        let intruder = Keypair::new();
+        svm.airdrop(&intruder.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let ix = build_checker_ix(&intruder, mint, fundraiser.0, vault, maker_ata);
+        let msg = Message::new(&[ix], Some(&intruder.pubkey()));
+        let tx = Transaction::new(&[&intruder], msg, svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err());
+
+        let ix = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
         svm.send_transaction(tx).unwrap();
-        msg!("Refund executed successfully (may transfer 0)");
+
+        // Maker received the funds, vault and fundraiser state are gone
+        assert_eq!(token_amount(&svm, &maker_ata), 10_000_000);
+        assert!(svm.get_account(&vault).map_or(true, |a| a.lamports == 0));
+        assert!(svm.get_account(&fundraiser.0).map_or(true, |a| a.lamports == 0));
+
+        // A completed campaign cannot be checked again
+        svm.expire_blockhash();
+        let ix = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err());
+        msg!("Checker swept the vault and closed the campaign");
+    }
+
+    #[test]
+    fn fundraiser_refund() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000_000).send().unwrap();
+
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
+
+        let end_ts = now(&svm) + DAY;
+        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, end_ts));
+        let msg = Message::new(&[init_ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // One second before the deadline the campaign is still running
+        warp_to(&mut svm, end_ts - 1);
+        let ix = build_refund_ix(&payer, &maker, mint, fundraiser.0, contributor_account, contributor_ata, vault);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1005);
+
+        // Ended with the target unmet: refund goes through
+        warp_to(&mut svm, end_ts);
+        svm.expire_blockhash();
+        let ix = build_refund_ix(&payer, &maker, mint, fundraiser.0, contributor_account, contributor_ata, vault);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(token_amount(&svm, &contributor_ata), 10_000_000);
+
+        // Nothing was contributed by the maker, so there is no record to refund
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&maker.pubkey()).send().unwrap();
+        let ix = build_refund_ix(&maker, &maker, mint, fundraiser.0, contributor_pda(&fundraiser.0, &maker.pubkey()), maker_ata, vault);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1010);
+        msg!("Refund after deadline succeeded, refund without contribution rejected");
+    }
+
+    #[test]
+    fn fundraiser_schedule_window() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000_000).send().unwrap();
+
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
+
+        // End before start is rejected
+        let start_ts = now(&svm) + DAY;
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs { start_ts, ..InitArgs::new(30_000_000, start_ts) });
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1012);
+
+        // Campaign opening tomorrow, running for 300 days (beyond the old u8 day limit)
+        let end_ts = start_ts + 300 * DAY;
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs { start_ts, ..InitArgs::new(30_000_000, end_ts) });
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1011);
+
+        warp_to(&mut svm, start_ts);
+        svm.expire_blockhash();
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Closed exactly at end_ts
+        warp_to(&mut svm, end_ts);
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_001);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1006);
+        msg!("Contribution window enforced to the second");
+    }
+
+    #[test]
+    fn fundraiser_refund_closes_contributor_and_rejects_double_refund() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000_000).send().unwrap();
+
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
+
+        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, now(&svm) + 30 * DAY));
+        let msg = Message::new(&[init_ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Contribute into the canonical contributor PDA (created by the program)
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert!(svm.get_account(&contributor_account).is_some());
+
+        let ix = build_cancel_ix(&maker, fundraiser.0);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let lamports_before = svm.get_balance(&payer.pubkey()).unwrap();
+        let ix = build_refund_ix(&payer, &maker, mint, fundraiser.0, contributor_account, contributor_ata, vault);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Tokens are back, the record is closed and its rent returned (net of the tx fee)
+        assert_eq!(token_amount(&svm, &contributor_ata), 10_000_000);
+        assert!(svm.get_account(&contributor_account).map_or(true, |a| a.lamports == 0));
+        assert!(svm.get_balance(&payer.pubkey()).unwrap() > lamports_before);
+
+        // Second refund must be rejected instead of draining the vault
+        svm.expire_blockhash();
+        let ix = build_refund_ix(&payer, &maker, mint, fundraiser.0, contributor_account, contributor_ata, vault);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1010);
+        msg!("Double refund rejected");
+    }
+
+    #[test]
+    fn fundraiser_cancel_unlocks_refund() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000_000).send().unwrap();
+
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
+
+        let init_ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, now(&svm) + 30 * DAY));
+        let msg = Message::new(&[init_ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(token_amount(&svm, &contributor_ata), 9_000_000);
+
+        // Campaign still running: refund must fail
+        let ix = build_refund_ix(&payer, &maker, mint, fundraiser.0, contributor_account, contributor_ata, vault);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err());
+
+        // Only the maker can cancel
+        let intruder = Keypair::new();
+        svm.airdrop(&intruder.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let ix = build_cancel_ix(&intruder, fundraiser.0);
+        let msg = Message::new(&[ix], Some(&intruder.pubkey()));
+        let tx = Transaction::new(&[&intruder], msg, svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err());
+
+        let ix = build_cancel_ix(&maker, fundraiser.0);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Contributions are closed once cancelled
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_001);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err());
+
+        // Refund is now available before the deadline (fresh blockhash: same ix as the failed attempt)
+        svm.expire_blockhash();
+        let ix = build_refund_ix(&payer, &maker, mint, fundraiser.0, contributor_account, contributor_ata, vault);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(token_amount(&svm, &contributor_ata), 10_000_000);
+        msg!("Cancel + early refund succeeded");
+    }
+
+    #[test]
+    fn fundraiser_multiple_campaigns_per_maker() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000_000).send().unwrap();
+
+        // Two concurrent campaigns from the same wallet, distinguished by campaign id
+        let first = fundraiser_pda(&maker.pubkey(), 1);
+        let second = fundraiser_pda(&maker.pubkey(), 2);
+        assert_ne!(first.0, second.0);
+        let first_vault = spl_associated_token_account::get_associated_token_address(&first.0, &mint);
+        let second_vault = spl_associated_token_account::get_associated_token_address(&second.0, &mint);
+
+        let ix1 = build_initialize_ix(&maker, mint, first.0, first_vault, &InitArgs { campaign_id: 1, ..InitArgs::new(30_000_000, now(&svm) + 30 * DAY) });
+        let ix2 = build_initialize_ix(&maker, mint, second.0, second_vault, &InitArgs { campaign_id: 2, ..InitArgs::new(20_000_000, now(&svm) + 30 * DAY) });
+        let msg = Message::new(&[ix1, ix2], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // The campaign id must match the PDA being initialized
+        let third = fundraiser_pda(&maker.pubkey(), 3);
+        let third_vault = spl_associated_token_account::get_associated_token_address(&third.0, &mint);
+        let ix = build_initialize_ix(&maker, mint, third.0, third_vault, &InitArgs { campaign_id: 4, ..InitArgs::new(30_000_000, now(&svm) + 30 * DAY) });
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        assert!(svm.send_transaction(tx).is_err());
+
+        // Contributions are tracked per campaign
+        let ix = build_contribute_ix(&payer, mint, first.0, contributor_pda(&first.0, &payer.pubkey()), contributor_ata, first_vault, 1_000_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        let ix = build_contribute_ix(&payer, mint, second.0, contributor_pda(&second.0, &payer.pubkey()), contributor_ata, second_vault, 2_000_000);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        assert_eq!(token_amount(&svm, &first_vault), 1_000_000);
+        assert_eq!(token_amount(&svm, &second_vault), 2_000_000);
+
+        // Cancelling and refunding one campaign signs with its own seeds and leaves the other untouched
+        let ix = build_cancel_ix(&maker, first.0);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        let ix = build_refund_ix(&payer, &maker, mint, first.0, contributor_pda(&first.0, &payer.pubkey()), contributor_ata, first_vault);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        assert_eq!(token_amount(&svm, &first_vault), 0);
+        assert_eq!(token_amount(&svm, &second_vault), 2_000_000);
+        msg!("Two campaigns from one maker handled independently");
+    }
+
+    #[test]
+    fn fundraiser_custom_contribution_limits() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(0).authority(&payer.pubkey()).send().unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000).send().unwrap();
+
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
+        let end_ts = now(&svm) + 30 * DAY;
+
+        // More than 100% in basis points is rejected
+        let args = InitArgs { min_contribution: 1, max_per_contributor: 10_001, ..InitArgs::new(1_000_000, end_ts) };
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &args);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1013);
+
+        // Minimum above the cap is rejected
+        let args = InitArgs { min_contribution: 3_000, max_per_contributor: 2_000, cap_kind: CAP_ABSOLUTE, ..InitArgs::new(1_000_000, end_ts) };
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &args);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1013);
+
+        // Absolute limits for a 0-decimal token: between 500 and 2_000 units per contributor
+        let args = InitArgs { min_contribution: 500, max_per_contributor: 2_000, cap_kind: CAP_ABSOLUTE, ..InitArgs::new(1_000_000, end_ts) };
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &args);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 499);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1003);
+
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 2_001);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1002);
+
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_500);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Cumulative contributions are capped too
+        let ix = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 600);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1004);
+        msg!("Per-campaign contribution limits enforced");
+    }
+
+    #[test]
+    fn fundraiser_hard_cap_and_early_end() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(0).authority(&payer.pubkey()).send().unwrap();
+        let maker_ata = spl_associated_token_account::get_associated_token_address(&maker.pubkey(), &mint);
+        let end_ts = now(&svm) + 30 * DAY;
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 10_000);
+        let (bob, bob_ata) = funded_contributor(&mut svm, &payer, &mint, 10_000);
+
+        // Hard cap below the target is rejected
+        let capped = fundraiser_pda(&maker.pubkey(), 0);
+        let capped_vault = spl_associated_token_account::get_associated_token_address(&capped.0, &mint);
+        let limits = InitArgs { min_contribution: 1, max_per_contributor: 2_000, cap_kind: CAP_ABSOLUTE, ..InitArgs::new(3_000, end_ts) };
+        let args = InitArgs { hard_cap: 2_999, ..limits };
+        let ix = build_initialize_ix(&maker, mint, capped.0, capped_vault, &args);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1014);
+
+        // Hard cap of 3_500 with partial fills
+        let args = InitArgs { hard_cap: 3_500, flags: FLAG_PARTIAL_FILL, ..args };
+        let ix = build_initialize_ix(&maker, mint, capped.0, capped_vault, &args);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = build_contribute_ix(&alice, mint, capped.0, contributor_pda(&capped.0, &alice.pubkey()), alice_ata, capped_vault, 2_000);
+        let msg = Message::new(&[ix], Some(&alice.pubkey()));
+        let tx = Transaction::new(&[&alice], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Bob asks for 2_000 but only the 1_500 left under the cap is taken
+        let ix = build_contribute_ix(&bob, mint, capped.0, contributor_pda(&capped.0, &bob.pubkey()), bob_ata, capped_vault, 2_000);
+        let msg = Message::new(&[ix], Some(&bob.pubkey()));
+        let tx = Transaction::new(&[&bob], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(token_amount(&svm, &capped_vault), 3_500);
+        assert_eq!(token_amount(&svm, &bob_ata), 8_500);
+
+        // Nothing fits anymore
+        let ix = build_contribute_ix(&bob, mint, capped.0, contributor_pda(&capped.0, &bob.pubkey()), bob_ata, capped_vault, 1);
+        let msg = Message::new(&[ix], Some(&bob.pubkey()));
+        let tx = Transaction::new(&[&bob], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1015);
+
+        // Second campaign closes as soon as its target is hit
+        let early = fundraiser_pda(&maker.pubkey(), 1);
+        let early_vault = spl_associated_token_account::get_associated_token_address(&early.0, &mint);
+        let args = InitArgs { campaign_id: 1, flags: FLAG_END_ON_TARGET, ..limits };
+        let ix = build_initialize_ix(&maker, mint, early.0, early_vault, &args);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = build_contribute_ix(&alice, mint, early.0, contributor_pda(&early.0, &alice.pubkey()), alice_ata, early_vault, 2_000);
+        let msg = Message::new(&[ix], Some(&alice.pubkey()));
+        let tx = Transaction::new(&[&alice], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        let ix = build_contribute_ix(&bob, mint, early.0, contributor_pda(&early.0, &bob.pubkey()), bob_ata, early_vault, 1_000);
+        let msg = Message::new(&[ix], Some(&bob.pubkey()));
+        let tx = Transaction::new(&[&bob], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = build_contribute_ix(&bob, mint, early.0, contributor_pda(&early.0, &bob.pubkey()), bob_ata, early_vault, 500);
+        let msg = Message::new(&[ix], Some(&bob.pubkey()));
+        let tx = Transaction::new(&[&bob], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1006);
+
+        // Checker runs well before the deadline
+        let ix = build_checker_ix(&maker, mint, early.0, early_vault, maker_ata);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(token_amount(&svm, &maker_ata), 3_000);
+        msg!("Hard cap and early end enforced");
+    }
+
+    #[test]
+    fn fundraiser_token_2022_transfer_fee() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+        let token_program = spl_token_2022::ID;
+
+        // 1% transfer fee, 0 decimals
+        let (mint, atas) = create_fee_mint_2022(&mut svm, &payer, 100, &[payer.pubkey()], 100_000);
+        let contributor_ata = atas[0];
+        let maker_ata = spl_associated_token_account::get_associated_token_address_with_program_id(&maker.pubkey(), &mint, &token_program);
+        let end_ts = now(&svm) + 30 * DAY;
+        let limits = InitArgs { min_contribution: 1, max_per_contributor: 100_000, cap_kind: CAP_ABSOLUTE, ..InitArgs::new(1_000, end_ts) };
+
+        // Refund path: the contributor is credited the net amount that reached the vault
+        let refunded = fundraiser_pda(&maker.pubkey(), 0);
+        let refunded_vault = spl_associated_token_account::get_associated_token_address_with_program_id(&refunded.0, &mint, &token_program);
+        let refunded_record = contributor_pda(&refunded.0, &payer.pubkey());
+        let ix = with_token_program(build_initialize_ix(&maker, mint, refunded.0, refunded_vault, &InitArgs { amount_to_raise: 100_000, ..limits }), token_program);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Any other program posing as the token program is rejected
+        let ix = with_token_program(build_contribute_ix(&payer, mint, refunded.0, refunded_record, contributor_ata, refunded_vault, 10_000), Pubkey::new_unique());
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1016);
+
+        let ix = with_token_program(build_contribute_ix(&payer, mint, refunded.0, refunded_record, contributor_ata, refunded_vault, 10_000), token_program);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(token_amount(&svm, &refunded_vault), 9_900);
+        assert_eq!(contributor_amount(&svm, &refunded_record), 9_900);
+
+        let ix = build_cancel_ix(&maker, refunded.0);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = with_token_program(build_refund_ix(&payer, &maker, mint, refunded.0, refunded_record, contributor_ata, refunded_vault), token_program);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        // 9_900 leaves the vault, minus 1% on the way out
+        assert_eq!(token_amount(&svm, &refunded_vault), 0);
+        assert_eq!(token_amount(&svm, &contributor_ata), 100_000 - 10_000 + 9_801);
+
+        // Checker path: withheld fees in the vault are harvested so it can be closed
+        let checked = fundraiser_pda(&maker.pubkey(), 1);
+        let checked_vault = spl_associated_token_account::get_associated_token_address_with_program_id(&checked.0, &mint, &token_program);
+        let checked_record = contributor_pda(&checked.0, &payer.pubkey());
+        let ix = with_token_program(build_initialize_ix(&maker, mint, checked.0, checked_vault, &InitArgs { campaign_id: 1, flags: FLAG_END_ON_TARGET, ..limits }), token_program);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let ix = with_token_program(build_contribute_ix(&payer, mint, checked.0, checked_record, contributor_ata, checked_vault, 2_000), token_program);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(token_amount(&svm, &checked_vault), 1_980);
+
+        let ix = with_token_program(build_checker_ix(&maker, mint, checked.0, checked_vault, maker_ata), token_program);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(token_amount(&svm, &maker_ata), 1_980 - 20);
+        assert!(svm.get_account(&checked_vault).map_or(true, |a| a.lamports == 0));
+        msg!("Token-2022 transfer-fee mint supported end to end");
+    }
+
+    #[test]
+    fn fundraiser_native_sol() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        // No mint or token accounts: the mint and ATA slots are placeholders
+        let no_mint = solana_sdk_ids::system_program::ID;
+        let rent_reserve = svm.minimum_balance_for_rent_exemption(0);
+        let end_ts = now(&svm) + 30 * DAY;
+        let limits = InitArgs {
+            min_contribution: LAMPORTS_PER_SOL / 10,
+            max_per_contributor: 2 * LAMPORTS_PER_SOL,
+            cap_kind: CAP_ABSOLUTE,
+            flags: FLAG_NATIVE_SOL,
+            ..InitArgs::new(2 * LAMPORTS_PER_SOL, end_ts)
+        };
+
+        // Campaign 0: contribute, cancel, refund lamports
+        let refunded = fundraiser_pda(&maker.pubkey(), 0);
+        let refunded_vault = native_vault_pda(&refunded.0);
+        let refunded_record = contributor_pda(&refunded.0, &payer.pubkey());
+        let ix = build_initialize_ix(&maker, no_mint, refunded.0, refunded_vault, &limits);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(svm.get_balance(&refunded_vault).unwrap(), rent_reserve);
+
+        let ix = build_contribute_ix(&payer, no_mint, refunded.0, refunded_record, payer.pubkey(), refunded_vault, LAMPORTS_PER_SOL);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(svm.get_balance(&refunded_vault).unwrap(), rent_reserve + LAMPORTS_PER_SOL);
+        assert_eq!(contributor_amount(&svm, &refunded_record), LAMPORTS_PER_SOL);
+
+        let ix = build_cancel_ix(&maker, refunded.0);
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let before = svm.get_balance(&payer.pubkey()).unwrap();
+        let ix = build_refund_ix(&payer, &maker, no_mint, refunded.0, refunded_record, payer.pubkey(), refunded_vault);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(svm.get_balance(&refunded_vault).unwrap(), rent_reserve);
+        assert!(svm.get_balance(&payer.pubkey()).unwrap() > before + LAMPORTS_PER_SOL - LAMPORTS_PER_SOL / 100);
+
+        // Campaign 1: reaches its target and pays the maker in SOL
+        let checked = fundraiser_pda(&maker.pubkey(), 1);
+        let checked_vault = native_vault_pda(&checked.0);
+        let checked_record = contributor_pda(&checked.0, &payer.pubkey());
+        let ix = build_initialize_ix(&maker, no_mint, checked.0, checked_vault, &InitArgs { campaign_id: 1, flags: FLAG_NATIVE_SOL | FLAG_END_ON_TARGET, ..limits });
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        // Another campaign's vault is not accepted
+        let ix = build_contribute_ix(&payer, no_mint, checked.0, checked_record, payer.pubkey(), refunded_vault, 2 * LAMPORTS_PER_SOL);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        assert_custom_error(svm.send_transaction(tx), 1017);
+
+        let ix = build_contribute_ix(&payer, no_mint, checked.0, checked_record, payer.pubkey(), checked_vault, 2 * LAMPORTS_PER_SOL);
+        let msg = Message::new(&[ix], Some(&payer.pubkey()));
+        let tx = Transaction::new(&[&payer], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+
+        let before = svm.get_balance(&maker.pubkey()).unwrap();
+        let ix = build_checker_ix(&maker, no_mint, checked.0, checked_vault, maker.pubkey());
+        let msg = Message::new(&[ix], Some(&maker.pubkey()));
+        let tx = Transaction::new(&[&maker], msg, svm.latest_blockhash());
+        svm.send_transaction(tx).unwrap();
+        assert!(svm.get_balance(&maker.pubkey()).unwrap() > before + 2 * LAMPORTS_PER_SOL);
+        assert!(svm.get_account(&checked_vault).map_or(true, |a| a.lamports == 0));
+        assert!(svm.get_account(&checked.0).map_or(true, |a| a.lamports == 0));
+        msg!("Native SOL campaign refunded and checked");
+    }
+
+    #[test]
+    fn fundraiser_rejects_substituted_accounts() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let other_mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let contributor_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&payer.pubkey()).send().unwrap();
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&maker.pubkey()).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &contributor_ata, 10_000_000).send().unwrap();
+
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let contributor_account = contributor_pda(&fundraiser.0, &payer.pubkey());
+        let args = InitArgs::new(30_000_000, now(&svm) + 30 * DAY);
+
+        // Initialize: the fundraiser must be the PDA for (maker, campaign_id) and the vault its ATA
+        let wrong_fundraiser = fundraiser_pda(&maker.pubkey(), 1).0;
+        let ix = build_initialize_ix(&maker, mint, wrong_fundraiser, vault, &args);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1018);
+        let other_vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &other_mint);
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, other_vault, &args);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1017);
+
+        // Program slots: [.., system_program (4), token_program (5), associated_token_program (6)]
+        let initialize = build_initialize_ix(&maker, mint, fundraiser.0, vault, &args);
+        assert_custom_error(send(&mut svm, substitute(initialize.clone(), 4, TOKEN_PROGRAM_ID), &[&maker]), 1054);
+        assert_custom_error(send(&mut svm, substitute(initialize.clone(), 5, Pubkey::new_unique()), &[&maker]), 1016);
+        assert_custom_error(send(&mut svm, substitute(initialize.clone(), 6, Pubkey::new_unique()), &[&maker]), 1054);
+        send(&mut svm, initialize, &[&maker]).unwrap();
+
+        // Contribute: [contributor, mint, fundraiser, contributor_account, contributor_ata, vault, token_program, system_program]
+        let contribute = build_contribute_ix(&payer, mint, fundraiser.0, contributor_account, contributor_ata, vault, 1_000_000);
+        assert_custom_error(send(&mut svm, substitute(contribute.clone(), 1, other_mint), &[&payer]), 1019);
+        assert_custom_error(send(&mut svm, substitute(contribute.clone(), 2, maker.pubkey()), &[&payer]), 1018);
+        let stranger_record = contributor_pda(&fundraiser.0, &maker.pubkey());
+        assert_custom_error(send(&mut svm, substitute(contribute.clone(), 3, stranger_record), &[&payer]), 1020);
+        assert_custom_error(send(&mut svm, substitute(contribute.clone(), 5, contributor_ata), &[&payer]), 1017);
+        assert_custom_error(send(&mut svm, substitute(contribute.clone(), 6, Pubkey::new_unique()), &[&payer]), 1016);
+        assert_custom_error(send(&mut svm, substitute(contribute.clone(), 7, Pubkey::new_unique()), &[&payer]), 1054);
+        send(&mut svm, contribute, &[&payer]).unwrap();
+
+        // Checker: [maker, mint, fundraiser, vault, maker_ata, token_program, system_program, associated_token_program, ..]
+        let checker = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
+        assert_custom_error(send(&mut svm, substitute(checker.clone(), 1, other_mint), &[&maker]), 1019);
+        assert_custom_error(send(&mut svm, substitute(checker.clone(), 2, vault), &[&maker]), 1018);
+        assert_custom_error(send(&mut svm, substitute(checker.clone(), 3, contributor_ata), &[&maker]), 1017);
+        assert_custom_error(send(&mut svm, substitute(checker.clone(), 5, Pubkey::new_unique()), &[&maker]), 1016);
+        assert_custom_error(send(&mut svm, substitute(checker.clone(), 6, TOKEN_PROGRAM_ID), &[&maker]), 1054);
+        assert_custom_error(send(&mut svm, substitute(checker, 7, Pubkey::new_unique()), &[&maker]), 1054);
+
+        // Refund: [contributor, maker, mint, fundraiser, contributor_account, contributor_ata, vault, token_program, system_program]
+        let refund = build_refund_ix(&payer, &maker, mint, fundraiser.0, contributor_account, contributor_ata, vault);
+        assert_custom_error(send(&mut svm, substitute(refund.clone(), 1, Pubkey::new_unique()), &[&payer]), 1021);
+        assert_custom_error(send(&mut svm, substitute(refund.clone(), 2, other_mint), &[&payer]), 1019);
+        assert_custom_error(send(&mut svm, substitute(refund.clone(), 3, maker.pubkey()), &[&payer]), 1018);
+        assert_custom_error(send(&mut svm, substitute(refund.clone(), 4, stranger_record), &[&payer]), 1020);
+        assert_custom_error(send(&mut svm, substitute(refund.clone(), 6, contributor_ata), &[&payer]), 1017);
+        assert_custom_error(send(&mut svm, substitute(refund.clone(), 7, Pubkey::new_unique()), &[&payer]), 1016);
+        assert_custom_error(send(&mut svm, substitute(refund, 8, Pubkey::new_unique()), &[&payer]), 1054);
+
+        // Cancel: [maker, fundraiser]
+        let ix = substitute(build_cancel_ix(&maker, fundraiser.0), 1, vault);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1018);
+
+        // Receipt campaign: receipt_mint and the contributor's receipt account follow the fixed accounts
+        let receipted = fundraiser_pda(&maker.pubkey(), 1);
+        let receipted_vault = spl_associated_token_account::get_associated_token_address(&receipted.0, &mint);
+        let receipted_record = contributor_pda(&receipted.0, &payer.pubkey());
+        let receipt_mint = receipt_mint_pda(&receipted.0);
+        let args = InitArgs { campaign_id: 1, flags: FLAG_RECEIPT, ..args };
+        send(&mut svm, with_accounts(build_initialize_ix(&maker, mint, receipted.0, receipted_vault, &args), &[receipt_mint]), &[&maker]).unwrap();
+        let payer_receipt = CreateAssociatedTokenAccount::new(&mut svm, &payer, &receipt_mint).owner(&payer.pubkey()).send().unwrap();
+        let maker_receipt = CreateAssociatedTokenAccount::new(&mut svm, &payer, &receipt_mint).owner(&maker.pubkey()).send().unwrap();
+
+        let contribute = with_accounts(
+            build_contribute_ix(&payer, mint, receipted.0, receipted_record, contributor_ata, receipted_vault, 1_000_000),
+            &[receipt_mint, payer_receipt],
+        );
+        assert_custom_error(send(&mut svm, substitute(contribute.clone(), 8, other_mint), &[&payer]), 1031);
+        assert_custom_error(send(&mut svm, substitute(contribute.clone(), 9, maker_receipt), &[&payer]), 1042);
+        send(&mut svm, contribute, &[&payer]).unwrap();
+
+        let refund = with_accounts(
+            build_refund_ix(&payer, &maker, mint, receipted.0, receipted_record, contributor_ata, receipted_vault),
+            &[receipt_mint, payer_receipt],
+        );
+        assert_custom_error(send(&mut svm, substitute(refund.clone(), 9, other_mint), &[&payer]), 1031);
+        assert_custom_error(send(&mut svm, substitute(refund, 10, maker_receipt), &[&payer]), 1042);
+
+        // None of the rejected attempts moved funds or minted receipts
+        assert_eq!(token_amount(&svm, &vault), 1_000_000);
+        assert_eq!(contributor_amount(&svm, &contributor_account), 1_000_000);
+        assert_eq!(token_amount(&svm, &receipted_vault), 1_000_000);
+        assert_eq!((token_amount(&svm, &payer_receipt), token_amount(&svm, &maker_receipt)), (1_000_000, 0));
+        msg!("Every substituted account rejected with a typed error");
+    }
+
+    #[test]
+    fn fundraiser_milestone_tranches() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 20_000_000);
+        let (bob, bob_ata) = funded_contributor(&mut svm, &payer, &mint, 4_000_000);
+        let maker_ata = spl_associated_token_account::get_associated_token_address(&maker.pubkey(), &mint);
+
+        let end_ts = now(&svm) + 30 * DAY;
+        let schedule = [(3_000, end_ts + DAY), (7_000, end_ts + 2 * DAY)];
+        let limits = InitArgs {
+            max_per_contributor: 10_000_000,
+            cap_kind: CAP_ABSOLUTE,
+            flags: FLAG_MILESTONES,
+            ..InitArgs::new(10_000_000, end_ts)
+        };
+
+        // Campaign 0 releases every tranche, campaign 1 is cancelled half way
+        let released = fundraiser_pda(&maker.pubkey(), 0);
+        let released_vault = spl_associated_token_account::get_associated_token_address(&released.0, &mint);
+        let released_milestones = milestones_pda(&released.0);
+        let cancelled = fundraiser_pda(&maker.pubkey(), 1);
+        let cancelled_vault = spl_associated_token_account::get_associated_token_address(&cancelled.0, &mint);
+        let cancelled_milestones = milestones_pda(&cancelled.0);
+
+        // Tranches have to add up to 100% and unlock in order after the deadline
+        let ix = with_milestones(build_initialize_ix(&maker, mint, released.0, released_vault, &limits), released_milestones, &[(3_000, end_ts + DAY), (6_000, end_ts + 2 * DAY)]);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1022);
+        let ix = with_milestones(build_initialize_ix(&maker, mint, released.0, released_vault, &limits), released_milestones, &[(3_000, end_ts + 2 * DAY), (7_000, end_ts + DAY)]);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1022);
+
+        let ix = with_milestones(build_initialize_ix(&maker, mint, released.0, released_vault, &limits), released_milestones, &schedule);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let ix = with_milestones(build_initialize_ix(&maker, mint, cancelled.0, cancelled_vault, &InitArgs { campaign_id: 1, ..limits }), cancelled_milestones, &schedule);
+        send(&mut svm, ix, &[&maker]).unwrap();
+
+        let ix = build_contribute_ix(&alice, mint, released.0, contributor_pda(&released.0, &alice.pubkey()), alice_ata, released_vault, 10_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, cancelled.0, contributor_pda(&cancelled.0, &alice.pubkey()), alice_ata, cancelled_vault, 6_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_contribute_ix(&bob, mint, cancelled.0, contributor_pda(&cancelled.0, &bob.pubkey()), bob_ata, cancelled_vault, 4_000_000);
+        send(&mut svm, ix, &[&bob]).unwrap();
+
+        // Checker does not apply to milestone campaigns, and nothing unlocks at the deadline itself
+        warp_to(&mut svm, end_ts);
+        let ix = build_checker_ix(&maker, mint, released.0, released_vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1025);
+        let ix = build_claim_tranche_ix(&maker, mint, released.0, released_milestones, released_vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1024);
+
+        // First tranche: 30% of each campaign
+        warp_to(&mut svm, end_ts + DAY);
+        svm.expire_blockhash();
+        let ix = build_claim_tranche_ix(&maker, mint, released.0, released_milestones, released_vault, maker_ata);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(token_amount(&svm, &maker_ata), 3_000_000);
+        assert_eq!(token_amount(&svm, &released_vault), 7_000_000);
+        let ix = build_claim_tranche_ix(&maker, mint, cancelled.0, cancelled_milestones, cancelled_vault, maker_ata);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(token_amount(&svm, &cancelled_vault), 7_000_000);
+
+        // The second tranche is still locked and a funded campaign cannot be refunded
+        svm.expire_blockhash();
+        let ix = build_claim_tranche_ix(&maker, mint, released.0, released_milestones, released_vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1024);
+        let ix = build_refund_ix(&alice, &maker, mint, released.0, contributor_pda(&released.0, &alice.pubkey()), alice_ata, released_vault);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1001);
+
+        // Cancelling stops the releases and refunds the unreleased 70% pro rata
+        let ix = build_cancel_ix(&maker, cancelled.0);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let ix = build_claim_tranche_ix(&maker, mint, cancelled.0, cancelled_milestones, cancelled_vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1009);
+        let ix = build_refund_ix(&alice, &maker, mint, cancelled.0, contributor_pda(&cancelled.0, &alice.pubkey()), alice_ata, cancelled_vault);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_refund_ix(&bob, &maker, mint, cancelled.0, contributor_pda(&cancelled.0, &bob.pubkey()), bob_ata, cancelled_vault);
+        send(&mut svm, ix, &[&bob]).unwrap();
+        assert_eq!(token_amount(&svm, &alice_ata), 20_000_000 - 10_000_000 - 6_000_000 + 4_200_000);
+        assert_eq!(token_amount(&svm, &bob_ata), 2_800_000);
+        assert_eq!(token_amount(&svm, &cancelled_vault), 0);
+
+        // Last tranche sweeps the vault and tears the campaign down
+        warp_to(&mut svm, end_ts + 2 * DAY);
+        svm.expire_blockhash();
+        let ix = build_claim_tranche_ix(&maker, mint, released.0, released_milestones, released_vault, maker_ata);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(token_amount(&svm, &maker_ata), 3_000_000 + 3_000_000 + 7_000_000);
+        assert!(svm.get_account(&released_vault).map_or(true, |a| a.lamports == 0));
+        assert!(svm.get_account(&released_milestones).map_or(true, |a| a.lamports == 0));
+        assert!(svm.get_account(&released.0).map_or(true, |a| a.lamports == 0));
+        msg!("Milestone tranches released on schedule, cancelled remainder refunded");
+    }
+
+    #[test]
+    fn fundraiser_contributor_governance() {
+        use crate::events::{Event, Voted};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 12_000_000);
+        let (bob, bob_ata) = funded_contributor(&mut svm, &payer, &mint, 8_000_000);
+        let maker_ata = spl_associated_token_account::get_associated_token_address(&maker.pubkey(), &mint);
+
+        let end_ts = now(&svm) + 30 * DAY;
+        let limits = InitArgs {
+            max_per_contributor: 10_000_000,
+            cap_kind: CAP_ABSOLUTE,
+            flags: FLAG_GOVERNANCE,
+            ..InitArgs::new(10_000_000, end_ts)
+        };
+
+        // Campaign 0 is approved, campaign 1 voted down
+        let approved = fundraiser_pda(&maker.pubkey(), 0);
+        let approved_vault = spl_associated_token_account::get_associated_token_address(&approved.0, &mint);
+        let rejected = fundraiser_pda(&maker.pubkey(), 1);
+        let rejected_vault = spl_associated_token_account::get_associated_token_address(&rejected.0, &mint);
+
+        // Quorum and threshold have to be within (0, 100%]
+        let ix = with_governance(build_initialize_ix(&maker, mint, approved.0, approved_vault, &limits), 0, 6_000);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1026);
+
+        // 50% of the weight has to vote, 60% of the votes cast have to approve
+        let ix = with_governance(build_initialize_ix(&maker, mint, approved.0, approved_vault, &limits), 5_000, 6_000);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let ix = with_governance(build_initialize_ix(&maker, mint, rejected.0, rejected_vault, &InitArgs { campaign_id: 1, ..limits }), 5_000, 6_000);
+        send(&mut svm, ix, &[&maker]).unwrap();
+
+        for (fundraiser, vault) in [(approved.0, approved_vault), (rejected.0, rejected_vault)] {
+            let ix = build_contribute_ix(&alice, mint, fundraiser, contributor_pda(&fundraiser, &alice.pubkey()), alice_ata, vault, 6_000_000);
+            send(&mut svm, ix, &[&alice]).unwrap();
+            let ix = build_contribute_ix(&bob, mint, fundraiser, contributor_pda(&fundraiser, &bob.pubkey()), bob_ata, vault, 4_000_000);
+            send(&mut svm, ix, &[&bob]).unwrap();
+        }
+
+        // Voting opens once the campaign is over
+        let ix = build_vote_ix(&bob, approved.0, contributor_pda(&approved.0, &bob.pubkey()), true);
+        assert_custom_error(send(&mut svm, ix, &[&bob]), 1005);
+        warp_to(&mut svm, end_ts);
+        svm.expire_blockhash();
+
+        // 4M of 10M approving is below quorum, so the maker cannot release yet
+        let ix = build_vote_ix(&bob, approved.0, contributor_pda(&approved.0, &bob.pubkey()), true);
+        let meta = send(&mut svm, ix, &[&bob]).unwrap();
+        assert_eq!(events(&meta), vec![Event::Voted(Voted {
+            fundraiser: approved.0.to_bytes(),
+            contributor: bob.pubkey().to_bytes(),
+            weight: 4_000_000,
+            votes_for: 4_000_000,
+            votes_against: 0,
+            vote_round: 0,
+            approve: 1,
+            _padding: [0; 6],
+        })]);
+        let ix = build_checker_ix(&maker, mint, approved.0, approved_vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1029);
+        svm.expire_blockhash();
+        let ix = build_vote_ix(&bob, approved.0, contributor_pda(&approved.0, &bob.pubkey()), false);
+        assert_custom_error(send(&mut svm, ix, &[&bob]), 1028);
+
+        // Alice brings turnout to 100%, all of it approving
+        let ix = build_vote_ix(&alice, approved.0, contributor_pda(&approved.0, &alice.pubkey()), true);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_checker_ix(&maker, mint, approved.0, approved_vault, maker_ata);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(token_amount(&svm, &maker_ata), 10_000_000);
+
+        // 6M against leaves at most 40% approval: the release is rejected outright
+        let ix = build_vote_ix(&alice, rejected.0, contributor_pda(&rejected.0, &alice.pubkey()), false);
+        let meta = send(&mut svm, ix, &[&alice]).unwrap();
+        assert!(matches!(events(&meta)[..], [Event::Voted(Voted { weight: 6_000_000, votes_for: 0, votes_against: 6_000_000, approve: 0, .. })]));
+        let ix = build_vote_ix(&bob, rejected.0, contributor_pda(&rejected.0, &bob.pubkey()), true);
+        assert_custom_error(send(&mut svm, ix, &[&bob]), 1030);
+        let ix = build_checker_ix(&maker, mint, rejected.0, rejected_vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1030);
+
+        // Target met, but a rejected release refunds the vault
+        let ix = build_refund_ix(&alice, &maker, mint, rejected.0, contributor_pda(&rejected.0, &alice.pubkey()), alice_ata, rejected_vault);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_refund_ix(&bob, &maker, mint, rejected.0, contributor_pda(&rejected.0, &bob.pubkey()), bob_ata, rejected_vault);
+        send(&mut svm, ix, &[&bob]).unwrap();
+        assert_eq!(token_amount(&svm, &alice_ata), 12_000_000 - 6_000_000);
+        assert_eq!(token_amount(&svm, &bob_ata), 8_000_000 - 4_000_000);
+        assert_eq!(token_amount(&svm, &rejected_vault), 0);
+        msg!("Releases gated by contributor votes, rejected release refunded");
+    }
+
+    #[test]
+    fn fundraiser_unapproved_release_lapses() {
+        use crate::constants::VOTING_PERIOD;
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 10_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let maker_ata = spl_associated_token_account::get_associated_token_address(&maker.pubkey(), &mint);
+        let alice_record = contributor_pda(&fundraiser.0, &alice.pubkey());
+
+        let end_ts = now(&svm) + 30 * DAY;
+        let args = InitArgs { max_per_contributor: 10_000_000, cap_kind: CAP_ABSOLUTE, flags: FLAG_GOVERNANCE, ..InitArgs::new(10_000_000, end_ts) };
+        let ix = with_governance(build_initialize_ix(&maker, mint, fundraiser.0, vault, &args), 5_000, 6_000);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 10_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+
+        // Nobody votes: while voting is open the release is pending, neither paid out nor refunded
+        warp_to(&mut svm, end_ts + VOTING_PERIOD - 1);
+        let ix = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1029);
+        let ix = build_refund_ix(&alice, &maker, mint, fundraiser.0, alice_record, alice_ata, vault);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1001);
+
+        // Once voting closes the release counts as rejected and the vault goes back
+        warp_to(&mut svm, end_ts + VOTING_PERIOD);
+        svm.expire_blockhash();
+        let ix = build_vote_ix(&alice, fundraiser.0, alice_record, true);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1048);
+        let ix = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1030);
+        let ix = build_refund_ix(&alice, &maker, mint, fundraiser.0, alice_record, alice_ata, vault);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(token_amount(&svm, &alice_ata), 10_000_000);
+        assert_eq!(token_amount(&svm, &vault), 0);
+        msg!("Release nobody approved in time refunded");
+    }
+
+    #[test]
+    fn fundraiser_pro_rata_refund_when_vault_short() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, now(&svm) + 30 * DAY));
+        send(&mut svm, ix, &[&maker]).unwrap();
+
+        let contributors: Vec<(Keypair, Pubkey)> = (0..3).map(|_| funded_contributor(&mut svm, &payer, &mint, 1_000_000)).collect();
+        for (contributor, contributor_ata) in &contributors {
+            let ix = build_contribute_ix(contributor, mint, fundraiser.0, contributor_pda(&fundraiser.0, &contributor.pubkey()), *contributor_ata, vault, 1_000_000);
+            send(&mut svm, ix, &[contributor]).unwrap();
+        }
+
+        // Something drained the vault: 1_999_999 left for 3_000_000 contributed
+        let mut vault_account = svm.get_account(&vault).unwrap();
+        vault_account.data[64..72].copy_from_slice(&1_999_999u64.to_le_bytes());
+        svm.set_account(vault, vault_account).unwrap();
+
+        let ix = build_cancel_ix(&maker, fundraiser.0);
+        send(&mut svm, ix, &[&maker]).unwrap();
+
+        // Shares round down; the last refunder sweeps what rounding left behind
+        for ((contributor, contributor_ata), expected) in contributors.iter().zip([666_666, 666_666, 666_667]) {
+            let ix = build_refund_ix(contributor, &maker, mint, fundraiser.0, contributor_pda(&fundraiser.0, &contributor.pubkey()), *contributor_ata, vault);
+            send(&mut svm, ix, &[contributor]).unwrap();
+            assert_eq!(token_amount(&svm, contributor_ata), expected);
+        }
+        assert_eq!(token_amount(&svm, &vault), 0);
+
+        // header (2) | maker (32) | campaign_id (8) | mint_to_raise (32) | amount_to_raise (8) | current_amount (8) | refunded_amount (8)
+        let state = svm.get_account(&fundraiser.0).unwrap();
+        assert_eq!(u64::from_le_bytes(state.data[82..90].try_into().unwrap()), 0);
+        assert_eq!(u64::from_le_bytes(state.data[90..98].try_into().unwrap()), 1_999_999);
+        msg!("Short vault refunded pro rata without dust");
+    }
+
+    #[test]
+    fn fundraiser_receipt_tokens() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let receipt_mint = receipt_mint_pda(&fundraiser.0);
+        let alice_record = contributor_pda(&fundraiser.0, &alice.pubkey());
+
+        let args = InitArgs { flags: FLAG_RECEIPT, ..InitArgs::new(30_000_000, now(&svm) + 30 * DAY) };
+        let ix = with_accounts(build_initialize_ix(&maker, mint, fundraiser.0, vault, &args), &[receipt_mint]);
+        send(&mut svm, ix, &[&maker]).unwrap();
+
+        // The receipt mint copies the raised mint's decimals and is controlled by the fundraiser
+        let receipt = svm.get_account(&receipt_mint).unwrap();
+        assert_eq!(receipt.owner, TOKEN_PROGRAM_ID);
+        assert_eq!(receipt.data[44], 6);
+        assert_eq!(&receipt.data[4..36], fundraiser.0.as_ref());
+        let alice_receipt = CreateAssociatedTokenAccount::new(&mut svm, &payer, &receipt_mint).owner(&alice.pubkey()).send().unwrap();
+
+        // Another mint cannot stand in for the receipt mint
+        let ix = with_accounts(build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 2_000_000), &[mint, alice_ata]);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1031);
+
+        let ix = with_accounts(build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 2_000_000), &[receipt_mint, alice_receipt]);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(token_amount(&svm, &alice_receipt), 2_000_000);
+
+        // Refunding burns the receipts along with the record
+        let ix = build_cancel_ix(&maker, fundraiser.0);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let ix = with_accounts(build_refund_ix(&alice, &maker, mint, fundraiser.0, alice_record, alice_ata, vault), &[receipt_mint, alice_receipt]);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(token_amount(&svm, &alice_receipt), 0);
+        assert_eq!(token_amount(&svm, &alice_ata), 3_000_000);
+        // mint_authority (36) | supply (8) | ...
+        let receipt = svm.get_account(&receipt_mint).unwrap();
+        assert_eq!(u64::from_le_bytes(receipt.data[36..44].try_into().unwrap()), 0);
+        msg!("Receipts minted on contribute and burned on refund");
+    }
+
+    #[test]
+    fn fundraiser_allowlist() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+        let (bob, bob_ata) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+        let (carol, _) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+        let (dave, dave_ata) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+
+        let allocations = [(alice.pubkey(), 2_000_000), (bob.pubkey(), 1_000_000), (carol.pubkey(), 1_000_000)];
+        let leaves: Vec<[u8; 32]> = allocations.iter().map(|(contributor, max_allocation)| allowlist_leaf(contributor, *max_allocation)).collect();
+        let (root, proofs) = allowlist_tree(&leaves);
+
+        let args = InitArgs { flags: FLAG_ALLOWLIST, ..InitArgs::new(30_000_000, now(&svm) + 30 * DAY) };
+        let mut ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &args);
+        ix.data.extend_from_slice(&root);
+        send(&mut svm, ix, &[&maker]).unwrap();
+
+        let alice_record = contributor_pda(&fundraiser.0, &alice.pubkey());
+        let ix = with_allowlist_proof(build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 1_500_000), 2_000_000, &proofs[0]);
+        send(&mut svm, ix, &[&alice]).unwrap();
+
+        // The allocation is cumulative and cannot be inflated
+        let ix = with_allowlist_proof(build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 1_000_000), 2_000_000, &proofs[0]);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1033);
+        let ix = with_allowlist_proof(build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 1_000_000), 3_000_000, &proofs[0]);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1032);
+
+        // Someone else's proof does not work for an address missing from the list
+        let ix = with_allowlist_proof(build_contribute_ix(&dave, mint, fundraiser.0, contributor_pda(&fundraiser.0, &dave.pubkey()), dave_ata, vault, 1_000_000), 2_000_000, &proofs[0]);
+        assert_custom_error(send(&mut svm, ix, &[&dave]), 1032);
+
+        let ix = with_allowlist_proof(build_contribute_ix(&bob, mint, fundraiser.0, contributor_pda(&fundraiser.0, &bob.pubkey()), bob_ata, vault, 1_000_000), 1_000_000, &proofs[1]);
+        send(&mut svm, ix, &[&bob]).unwrap();
+        assert_eq!(token_amount(&svm, &vault), 2_500_000);
+        msg!("Allowlist proofs and allocations enforced");
+    }
+
+    #[test]
+    fn fundraiser_platform_fee() {
+        use crate::events::{Event, TrancheClaimed};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        let admin = Keypair::new();
+        let platform = Pubkey::new_unique();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+        svm.airdrop(&admin.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        // Only the upgrade authority can create the config, fees are capped and nobody but the
+        // admin can reconfigure
+        set_upgrade_authority(&mut svm, &admin.pubkey());
+        let ix = build_set_config_ix(&maker, 0, maker.pubkey(), maker.pubkey());
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1047);
+        let ix = build_set_config_ix(&admin, 1_500, platform, admin.pubkey());
+        assert_custom_error(send(&mut svm, ix, &[&admin]), 1035);
+        let ix = build_set_config_ix(&admin, 250, platform, admin.pubkey());
+        send(&mut svm, ix, &[&admin]).unwrap();
+        let ix = build_set_config_ix(&maker, 0, maker.pubkey(), maker.pubkey());
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1008);
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 20_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let maker_ata = spl_associated_token_account::get_associated_token_address(&maker.pubkey(), &mint);
+        let platform_ata = spl_associated_token_account::get_associated_token_address(&platform, &mint);
+
+        let args = InitArgs { max_per_contributor: 10_000_000, cap_kind: CAP_ABSOLUTE, flags: FLAG_END_ON_TARGET, ..InitArgs::new(10_000_000, now(&svm) + 30 * DAY) };
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &args);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, contributor_pda(&fundraiser.0, &alice.pubkey()), alice_ata, vault, 10_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+
+        // The maker cannot route the fee to themselves
+        let ix = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1036);
+
+        let ix = with_fee_recipient(build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata), platform, platform_ata);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(token_amount(&svm, &platform_ata), 250_000);
+        assert_eq!(token_amount(&svm, &maker_ata), 9_750_000);
+
+        // Milestone campaigns pay the fee on every tranche
+        let end_ts = now(&svm) + 30 * DAY;
+        let milestone = fundraiser_pda(&maker.pubkey(), 1);
+        let milestone_vault = spl_associated_token_account::get_associated_token_address(&milestone.0, &mint);
+        let milestones = milestones_pda(&milestone.0);
+        let args = InitArgs { campaign_id: 1, max_per_contributor: 10_000_000, cap_kind: CAP_ABSOLUTE, flags: FLAG_MILESTONES, ..InitArgs::new(10_000_000, end_ts) };
+        let ix = with_milestones(build_initialize_ix(&maker, mint, milestone.0, milestone_vault, &args), milestones, &[(4_000, end_ts + DAY), (6_000, end_ts + 2 * DAY)]);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, milestone.0, contributor_pda(&milestone.0, &alice.pubkey()), alice_ata, milestone_vault, 10_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+
+        warp_to(&mut svm, end_ts + DAY);
+        let ix = build_claim_tranche_ix(&maker, mint, milestone.0, milestones, milestone_vault, maker_ata);
+        assert_custom_error(send(&mut svm, ix, &[&maker]), 1036);
+        let ix = with_fee_recipient(build_claim_tranche_ix(&maker, mint, milestone.0, milestones, milestone_vault, maker_ata), platform, platform_ata);
+        let meta = send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(events(&meta).last(), Some(&Event::TrancheClaimed(TrancheClaimed {
+            fundraiser: milestone.0.to_bytes(),
+            maker: maker.pubkey().to_bytes(),
+            amount: 4_000_000,
+            fee_amount: 100_000,
+            remaining: 6_000_000,
+            tranche_index: 0,
+            _padding: [0; 7],
+        })));
+        assert_eq!(token_amount(&svm, &platform_ata), 250_000 + 100_000);
+        assert_eq!(token_amount(&svm, &maker_ata), 9_750_000 + 3_900_000);
+
+        warp_to(&mut svm, end_ts + 2 * DAY);
+        let ix = with_fee_recipient(build_claim_tranche_ix(&maker, mint, milestone.0, milestones, milestone_vault, maker_ata), platform, platform_ata);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(token_amount(&svm, &platform_ata), 250_000 + 100_000 + 150_000);
+        assert_eq!(token_amount(&svm, &maker_ata), 9_750_000 + 3_900_000 + 5_850_000);
+        msg!("Checker and every tranche split the raise between maker and platform");
+    }
+
+    #[test]
+    fn fundraiser_emits_events() {
+        use crate::events::{Cancelled, Contributed, Event, Initialized, Refunded};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 7);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let alice_record = contributor_pda(&fundraiser.0, &alice.pubkey());
+
+        let start_ts = now(&svm);
+        let args = InitArgs { campaign_id: 7, start_ts, ..InitArgs::new(30_000_000, start_ts + 30 * DAY) };
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &args);
+        let meta = send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(events(&meta), vec![Event::Initialized(Initialized {
+            fundraiser: fundraiser.0.to_bytes(),
+            maker: maker.pubkey().to_bytes(),
+            mint_to_raise: mint.to_bytes(),
+            amount_to_raise: 30_000_000,
+            start_ts,
+            end_ts: start_ts + 30 * DAY,
+            campaign_id: 7,
+            hard_cap: 0,
+        })]);
+
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 2_000_000);
+        let meta = send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(events(&meta), vec![Event::Contributed(Contributed {
+            fundraiser: fundraiser.0.to_bytes(),
+            contributor: alice.pubkey().to_bytes(),
+            amount: 2_000_000,
+            contributor_total: 2_000_000,
+            current_amount: 2_000_000,
+        })]);
+
+        let ix = build_cancel_ix(&maker, fundraiser.0);
+        let meta = send(&mut svm, ix, &[&maker]).unwrap();
+        assert_eq!(events(&meta), vec![Event::Cancelled(Cancelled {
+            fundraiser: fundraiser.0.to_bytes(),
+            maker: maker.pubkey().to_bytes(),
+        })]);
+
+        let ix = build_refund_ix(&alice, &maker, mint, fundraiser.0, alice_record, alice_ata, vault);
+        let meta = send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(events(&meta), vec![Event::Refunded(Refunded {
+            fundraiser: fundraiser.0.to_bytes(),
+            contributor: alice.pubkey().to_bytes(),
+            amount: 2_000_000,
+            payout: 2_000_000,
+            current_amount: 0,
+        })]);
+
+        // Anything that is not a fundraiser event is ignored
+        assert_eq!(Event::decode(&[9, 0, 0]), None);
+        assert_eq!(Event::decode(&[0, 1, 2]), None);
+        msg!("Events decoded from transaction logs");
+    }
+
+    #[test]
+    fn client_matches_the_wire_format() {
+        use crate::client::{self, AllowlistProof, Campaign, ContributionCapKind, InitializeArgs};
+
+        let maker = Keypair::new();
+        let alice = Keypair::new();
+        let fee_recipient = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata = |wallet: &Pubkey, mint: &Pubkey| spl_associated_token_account::get_associated_token_address(wallet, mint);
+
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 3).0;
+        let vault = ata(&fundraiser, &mint);
+        let alice_ata = ata(&alice.pubkey(), &mint);
+        let alice_record = contributor_pda(&fundraiser, &alice.pubkey());
+        let receipt_mint = receipt_mint_pda(&fundraiser);
+        let alice_receipt = ata(&alice.pubkey(), &receipt_mint);
+        let milestones = milestones_pda(&fundraiser);
+
+        let campaign = Campaign {
+            maker: maker.pubkey(),
+            campaign_id: 3,
+            mint: Some(mint),
+            token_program: TOKEN_PROGRAM_ID,
+            flags: FLAG_MILESTONES | FLAG_GOVERNANCE | FLAG_RECEIPT | FLAG_ALLOWLIST,
+            legacy_address: false,
+        };
+        assert_eq!(campaign.fundraiser(), fundraiser);
+        assert_eq!(campaign.vault(), vault);
+        assert_eq!(client::config_address().0, config_pda());
+
+        let tranches = [(4_000u16, 2_000i64), (6_000, 3_000)];
+        let (root, proof) = ([7u8; 32], [[1u8; 32], [2u8; 32]]);
+        let args = InitArgs { campaign_id: 3, flags: campaign.flags, ..InitArgs::new(30_000_000, 1_000) };
+        let mut expected = with_governance(
+            with_milestones(build_initialize_ix(&maker, mint, fundraiser, vault, &args), milestones, &tranches),
+            5_000,
+            6_000,
+        );
+        expected.data.extend_from_slice(&root);
+        let expected = with_accounts(expected, &[receipt_mint]);
+        let ix = client::initialize(&campaign, &InitializeArgs {
+            amount_to_raise: 30_000_000,
+            start_ts: 0,
+            end_ts: 1_000,
+            min_contribution: 1_000_000,
+            max_per_contributor: 1_000,
+            cap_kind: ContributionCapKind::BasisPoints,
+            hard_cap: 0,
+            tranches: tranches.to_vec(),
+            quorum_bps: 5_000,
+            approval_threshold_bps: 6_000,
+            allowlist_root: root,
+            stretch_goals: Vec::new(),
+            withdrawals: None,
+        });
+        assert_eq!(ix, expected);
+
+        let expected = with_accounts(
+            with_allowlist_proof(build_contribute_ix(&alice, mint, fundraiser, alice_record, alice_ata, vault, 2_000_000), 3_000_000, &proof),
+            &[receipt_mint, alice_receipt],
+        );
+        let allowlist = AllowlistProof { max_allocation: 3_000_000, proof: &proof };
+        assert_eq!(client::contribute(&campaign, &alice.pubkey(), 2_000_000, Some(allowlist)), expected);
+
+        let expected = with_accounts(build_refund_ix(&alice, &maker, mint, fundraiser, alice_record, alice_ata, vault), &[receipt_mint, alice_receipt]);
+        assert_eq!(client::refund(&campaign, &alice.pubkey()), expected);
+
+        let expected = with_fee_recipient(build_checker_ix(&maker, mint, fundraiser, vault, ata(&maker.pubkey(), &mint)), fee_recipient, ata(&fee_recipient, &mint));
+        assert_eq!(client::checker(&campaign, Some(&fee_recipient)), expected);
+        let expected = build_checker_ix(&maker, mint, fundraiser, vault, ata(&maker.pubkey(), &mint));
+        assert_eq!(client::checker(&campaign, None), expected);
+
+        assert_eq!(client::cancel(&campaign), build_cancel_ix(&maker, fundraiser));
+        assert_eq!(
+            client::close(&campaign),
+            with_accounts(build_close_ix(&maker, mint, fundraiser, vault, ata(&maker.pubkey(), &mint)), &[milestones]),
+        );
+        assert_eq!(
+            client::claim_tranche(&campaign, None),
+            build_claim_tranche_ix(&maker, mint, fundraiser, milestones, vault, ata(&maker.pubkey(), &mint)),
+        );
+        // Stretch-goal campaigns record their tier in the settlement account
+        let stretch = Campaign { flags: campaign.flags | FLAG_STRETCH_GOALS, ..campaign };
+        let settlement = client::settlement_address(&fundraiser).0;
+        assert_eq!(
+            client::checker(&stretch, None),
+            with_accounts(build_checker_ix(&maker, mint, fundraiser, vault, ata(&maker.pubkey(), &mint)), &[settlement]),
+        );
+        assert_eq!(
+            client::claim_tranche(&stretch, None),
+            with_accounts(build_claim_tranche_ix(&maker, mint, fundraiser, milestones, vault, ata(&maker.pubkey(), &mint)), &[settlement]),
+        );
+        assert_eq!(client::vote(&campaign, &alice.pubkey(), true), build_vote_ix(&alice, fundraiser, alice_record, true));
+        let expected = with_accounts(
+            build_withdraw_ix(&alice, &maker, mint, fundraiser, alice_record, alice_ata, vault, 1_000_000),
+            &[receipt_mint, alice_receipt],
+        );
+        assert_eq!(client::withdraw(&campaign, &alice.pubkey(), 1_000_000), expected);
+        assert_eq!(
+            client::migrate_contributor(&maker.pubkey(), &campaign, &alice.pubkey()),
+            with_readonly_accounts(build_migrate_ix(&maker, alice_record), &[fundraiser, alice.pubkey()]),
+        );
+        assert_eq!(
+            client::set_config(&maker.pubkey(), 250, &fee_recipient, &alice.pubkey()),
+            build_set_config_ix(&maker, 250, fee_recipient, alice.pubkey()),
+        );
+
+        // Native SOL campaigns use the lamport vault and wallet placeholders for token accounts
+        let native = Campaign { mint: None, flags: FLAG_NATIVE_SOL, ..campaign };
+        let native_vault = native_vault_pda(&fundraiser);
+        assert_eq!(native.vault(), native_vault);
+        let no_mint = solana_sdk_ids::system_program::ID;
+        assert_eq!(
+            client::contribute(&native, &alice.pubkey(), LAMPORTS_PER_SOL, None),
+            build_contribute_ix(&alice, no_mint, fundraiser, alice_record, alice.pubkey(), native_vault, LAMPORTS_PER_SOL),
+        );
+        msg!("Client instructions match the hand-built ones");
+    }
+
+    #[test]
+    fn client_decodes_campaign_accounts() {
+        use crate::client::{self, Campaign, ContributionCapKind, Contributor, FundRaiser, FundRaiserStatus, InitializeArgs};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, _) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+        let campaign = Campaign { maker: maker.pubkey(), campaign_id: 9, mint: Some(mint), token_program: TOKEN_PROGRAM_ID, flags: FLAG_END_ON_TARGET, legacy_address: false };
+
+        let end_ts = now(&svm) + 30 * DAY;
+        let ix = client::initialize(&campaign, &InitializeArgs {
+            amount_to_raise: 30_000_000,
+            start_ts: 0,
+            end_ts,
+            min_contribution: 1_000_000,
+            max_per_contributor: 2_500_000,
+            cap_kind: ContributionCapKind::Absolute,
+            hard_cap: 0,
+            tranches: Vec::new(),
+            quorum_bps: 0,
+            approval_threshold_bps: 0,
+            allowlist_root: [0; 32],
+            stretch_goals: Vec::new(),
+            withdrawals: None,
+        });
+        send(&mut svm, ix, &[&maker]).unwrap();
+        send(&mut svm, client::contribute(&campaign, &alice.pubkey(), 2_000_000, None), &[&alice]).unwrap();
+
+        let account = svm.get_account(&campaign.fundraiser()).unwrap();
+        let state = FundRaiser::from_bytes(&account.data).unwrap();
+        assert_eq!(state.maker(), maker.pubkey().to_bytes());
+        assert_eq!(state.mint_to_raise(), mint.to_bytes());
+        assert_eq!(state.current_amount(), 2_000_000);
+        assert_eq!(state.end_ts(), end_ts);
+        assert_eq!(state.max_contribution_amount().unwrap(), 2_500_000);
+        assert_eq!(state.status().unwrap(), FundRaiserStatus::Active);
+        assert_eq!(Campaign::from_state(state, TOKEN_PROGRAM_ID), campaign);
+
+        let account = svm.get_account(&campaign.contributor_account(&alice.pubkey())).unwrap();
+        assert_eq!(Contributor::from_bytes(&account.data).unwrap().amount(), 2_000_000);
+
+        // Data of the wrong size is rejected instead of misread
+        assert!(FundRaiser::from_bytes(&account.data).is_err());
+        msg!("Client decoded the campaign accounts");
+    }
+
+    #[test]
+    fn fundraiser_migrates_legacy_accounts() {
+        use crate::client::{Contributor, FundRaiser};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let alice_record = contributor_pda(&fundraiser.0, &alice.pubkey());
+
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, now(&svm) + 30 * DAY));
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 1_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let current = svm.get_account(&fundraiser.0).unwrap();
+
+        let migrate_alice = || with_readonly_accounts(build_migrate_ix(&payer, alice_record), &[fundraiser.0, alice.pubkey()]);
+
+        // Version 1 fundraisers are turned away until migrated, then gain the later fields, zeroed,
+        // with rent topped up for the new size
+        downgrade_layout(&mut svm, &fundraiser.0, 1, FundRaiser::LEN_V1);
+        svm.expire_blockhash();
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 1_000_000);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1037);
+        send(&mut svm, build_migrate_ix(&payer, fundraiser.0), &[&payer]).unwrap();
+        let migrated = svm.get_account(&fundraiser.0).unwrap();
+        assert_eq!(migrated.data, current.data);
+        assert_eq!(migrated.lamports, svm.minimum_balance_for_rent_exemption(current.data.len()));
+
+        // Migrating a current account again changes nothing
+        svm.expire_blockhash();
+        send(&mut svm, build_migrate_ix(&payer, fundraiser.0), &[&payer]).unwrap();
+        assert_eq!(svm.get_account(&fundraiser.0).unwrap().data, current.data);
+
+        // Version 2 fundraisers gain the withdrawal terms, disabled
+        downgrade_layout(&mut svm, &fundraiser.0, 2, FundRaiser::LEN_V2);
+        svm.expire_blockhash();
+        send(&mut svm, build_migrate_ix(&payer, fundraiser.0), &[&payer]).unwrap();
+        assert_eq!(svm.get_account(&fundraiser.0).unwrap().data, current.data);
+
+        // Version 1 contributors gain the last contribution time, zeroed, and every older record its bump
+        downgrade_layout(&mut svm, &alice_record, 1, Contributor::LEN_V1);
+        svm.expire_blockhash();
+        assert!(send(&mut svm, build_migrate_ix(&payer, alice_record), &[&payer]).is_err());
+        send(&mut svm, migrate_alice(), &[&payer]).unwrap();
+        let record = svm.get_account(&alice_record).unwrap();
+        assert_eq!(record.data.len(), Contributor::LEN);
+        assert_eq!(record.data[Contributor::LEN - 1], Pubkey::find_program_address(&[b"contributor", fundraiser.0.as_ref(), alice.pubkey().as_ref()], &fr_program_id()).1);
+
+        // Only this program's accounts can be migrated
+        assert!(send(&mut svm, build_migrate_ix(&payer, vault), &[&payer]).is_err());
+
+        svm.expire_blockhash();
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 1_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(contributor_amount(&svm, &alice_record), 2_000_000);
+        assert_eq!(token_amount(&svm, &vault), 2_000_000);
+        msg!("Older fundraiser and contributor layouts migrated in place");
+    }
+
+    #[test]
+    fn fundraiser_migrates_unversioned_accounts() {
+        use crate::client::{self, Campaign, FundRaiser};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 3_000_000);
+
+        // A campaign written by the unversioned program: `[b"fundraiser", maker]`, no header, a start
+        // time and a duration in days; alice's record is just her amount
+        let (fundraiser, bump) = Pubkey::find_program_address(&[b"fundraiser", maker.pubkey().as_ref()], &fr_program_id());
+        let vault = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&fundraiser).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &vault, 1_000_000).send().unwrap();
+        let alice_record = contributor_pda(&fundraiser, &alice.pubkey());
+        let time_started = now(&svm);
+        let legacy_fundraiser = legacy_fundraiser_data(&maker.pubkey(), &mint, 30_000_000, 1_000_000, time_started, bump);
+        assert_eq!(legacy_fundraiser.len(), FundRaiser::LEGACY_LEN);
+        set_program_account(&mut svm, fundraiser, legacy_fundraiser);
+        set_program_account(&mut svm, alice_record, le_u64(1_000_000).to_vec());
+
+        let ix = build_contribute_ix(&alice, mint, fundraiser, alice_record, alice_ata, vault, 1_000_000);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1037);
+
+        // The conversion needs the mint for the minimum contribution and the vault
+        let migrate = client::migrate_legacy_fundraiser(&payer.pubkey(), &maker.pubkey(), &mint, &TOKEN_PROGRAM_ID);
+        assert_eq!(migrate, with_readonly_accounts(build_migrate_ix(&payer, fundraiser), &[mint, TOKEN_PROGRAM_ID, vault]));
+        let ix = substitute(migrate.clone(), 5, alice_ata);
+        assert_custom_error(send(&mut svm, ix, &[&payer]), 1017);
+        assert!(send(&mut svm, build_migrate_ix(&payer, fundraiser), &[&payer]).is_err());
+
+        // Records are claimed against the converted fundraiser, so it goes first
+        let migrate_record = with_readonly_accounts(
+            with_accounts(build_migrate_ix(&payer, alice_record), &[fundraiser]),
+            &[alice.pubkey()],
+        );
+        let campaign = Campaign { maker: maker.pubkey(), campaign_id: 0, mint: Some(mint), token_program: TOKEN_PROGRAM_ID, flags: 0, legacy_address: true };
+        assert_eq!(client::migrate_contributor(&payer.pubkey(), &campaign, &alice.pubkey()), migrate_record);
+        assert_custom_error(send(&mut svm, migrate_record.clone(), &[&payer]), 1037);
+        send(&mut svm, migrate, &[&payer]).unwrap();
+        svm.expire_blockhash();
+        send(&mut svm, migrate_record, &[&payer]).unwrap();
+
+        // The old terms carry over: the window, one whole token minimum and a 10% cap
+        let account = svm.get_account(&fundraiser).unwrap();
+        assert_eq!(account.lamports, svm.minimum_balance_for_rent_exemption(FundRaiser::LEN));
+        let state = FundRaiser::from_bytes(&account.data).unwrap();
+        assert_eq!(Pubkey::new_from_array(state.maker()), maker.pubkey());
+        assert_eq!(Pubkey::new_from_array(state.mint_to_raise()), mint);
+        assert_eq!((state.amount_to_raise(), state.current_amount()), (30_000_000, 1_000_000));
+        assert_eq!((state.start_ts(), state.end_ts()), (time_started, time_started + 30 * DAY));
+        assert_eq!((state.min_contribution(), state.max_per_contributor()), (1_000_000, 1_000));
+        assert_eq!(state.cap_kind().unwrap(), client::ContributionCapKind::BasisPoints);
+        assert!(state.has_legacy_address());
+        assert_eq!(state.legacy_unclaimed_amount(), 0);
+        assert_eq!(Campaign::from_state(state, TOKEN_PROGRAM_ID), campaign);
+        assert_eq!(contributor_amount(&svm, &alice_record), 1_000_000);
+
+        // The campaign keeps working at its old address, signing with the legacy seeds
+        let ix = build_contribute_ix(&alice, mint, fundraiser, alice_record, alice_ata, vault, 500_000);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1003);
+        let ix = build_contribute_ix(&alice, mint, fundraiser, alice_record, alice_ata, vault, 1_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(contributor_amount(&svm, &alice_record), 2_000_000);
+
+        warp_to(&mut svm, time_started + 30 * DAY);
+        let ix = build_refund_ix(&alice, &maker, mint, fundraiser, alice_record, alice_ata, vault);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(token_amount(&svm, &alice_ata), 3_000_000 - 1_000_000 + 2_000_000);
+        msg!("Unversioned fundraiser and contributor accounts converted in place");
+    }
+
+    #[test]
+    fn fundraiser_rejects_unversioned_campaigns_already_paid_out() {
+        use crate::client;
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+
+        // The old checker paid the vault to the maker and closed nothing, so the fundraiser still
+        // reports what it raised over an empty vault
+        let (fundraiser, bump) = Pubkey::find_program_address(&[b"fundraiser", maker.pubkey().as_ref()], &fr_program_id());
+        let vault = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&fundraiser).send().unwrap();
+        let legacy_fundraiser = legacy_fundraiser_data(&maker.pubkey(), &mint, 3_000_000, 3_000_000, now(&svm) - DAY, bump);
+        set_program_account(&mut svm, fundraiser, legacy_fundraiser);
+
+        let migrate = client::migrate_legacy_fundraiser(&payer.pubkey(), &maker.pubkey(), &mint, &TOKEN_PROGRAM_ID);
+        assert_custom_error(send(&mut svm, migrate.clone(), &[&payer]), 1052);
+        assert_eq!(svm.get_account(&fundraiser).unwrap().data.len(), client::FundRaiser::LEGACY_LEN);
+
+        // Without a vault at all it is just as settled
+        svm.set_account(vault, solana_account::Account::default()).unwrap();
+        svm.expire_blockhash();
+        assert_custom_error(send(&mut svm, migrate, &[&payer]), 1052);
+        msg!("Unversioned campaigns already paid out stay unconverted");
+    }
+
+    #[test]
+    fn fundraiser_rejects_unversioned_records_already_refunded() {
+        use crate::client::{self, Campaign, FundRaiser};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 1_000_000);
+        let (bob, bob_ata) = funded_contributor(&mut svm, &payer, &mint, 0);
+
+        // Alice and bob each gave 1 token to a campaign that fell short; alice refunded under the
+        // unversioned program, which paid her and lowered the total but left her record as it was
+        let (fundraiser, bump) = Pubkey::find_program_address(&[b"fundraiser", maker.pubkey().as_ref()], &fr_program_id());
+        let vault = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&fundraiser).send().unwrap();
+        MintTo::new(&mut svm, &payer, &mint, &vault, 1_000_000).send().unwrap();
+        let alice_record = contributor_pda(&fundraiser, &alice.pubkey());
+        let bob_record = contributor_pda(&fundraiser, &bob.pubkey());
+        let time_started = now(&svm) - 31 * DAY;
+        let legacy_fundraiser = legacy_fundraiser_data(&maker.pubkey(), &mint, 30_000_000, 1_000_000, time_started, bump);
+        set_program_account(&mut svm, fundraiser, legacy_fundraiser);
+        set_program_account(&mut svm, alice_record, le_u64(1_000_000).to_vec());
+        set_program_account(&mut svm, bob_record, le_u64(1_000_000).to_vec());
+
+        let campaign = Campaign { maker: maker.pubkey(), campaign_id: 0, mint: Some(mint), token_program: TOKEN_PROGRAM_ID, flags: 0, legacy_address: true };
+        send(&mut svm, client::migrate_legacy_fundraiser(&payer.pubkey(), &maker.pubkey(), &mint, &TOKEN_PROGRAM_ID), &[&payer]).unwrap();
+        send(&mut svm, client::migrate_contributor(&payer.pubkey(), &campaign, &bob.pubkey()), &[&payer]).unwrap();
+
+        // What is left belongs to bob: alice's stale record claims more than the fundraiser still holds
+        let ix = client::migrate_contributor(&payer.pubkey(), &campaign, &alice.pubkey());
+        assert_custom_error(send(&mut svm, ix, &[&payer]), 1051);
+        assert_eq!(svm.get_account(&alice_record).unwrap().data.len(), 8);
+        let ix = build_refund_ix(&alice, &maker, mint, fundraiser, alice_record, alice_ata, vault);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1037);
+
+        let ix = build_refund_ix(&bob, &maker, mint, fundraiser, bob_record, bob_ata, vault);
+        send(&mut svm, ix, &[&bob]).unwrap();
+        assert_eq!(token_amount(&svm, &bob_ata), 1_000_000);
+        assert_eq!(token_amount(&svm, &alice_ata), 1_000_000);
+        let account = svm.get_account(&fundraiser).unwrap();
+        let state = FundRaiser::from_bytes(&account.data).unwrap();
+        assert_eq!((state.current_amount(), state.legacy_unclaimed_amount()), (0, 0));
+        msg!("Unversioned records refunded under the old program are not carried over");
+    }
+
+    #[test]
+    fn fundraiser_update_locks_terms_after_first_contribution() {
+        use crate::client::FundRaiser;
+        use crate::events::{Event, Updated};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 5_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let alice_record = contributor_pda(&fundraiser.0, &alice.pubkey());
+
+        let start = now(&svm);
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, start + 30 * DAY));
+        send(&mut svm, ix, &[&maker]).unwrap();
+
+        // Before anyone contributes the maker can rewrite the terms, shorter deadline included
+        let terms = InitArgs {
+            cap_kind: CAP_ABSOLUTE,
+            max_per_contributor: 5_000_000,
+            hard_cap: 50_000_000,
+            ..InitArgs::new(40_000_000, start + 10 * DAY)
+        };
+        assert_custom_error(send(&mut svm, build_update_ix(&alice, fundraiser.0, &terms), &[&alice]), 1008);
+        assert_custom_error(send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { hard_cap: 35_000_000, ..terms }), &[&maker]), 1014);
+        assert_custom_error(send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { end_ts: start - 1, ..terms }), &[&maker]), 1012);
+        assert_custom_error(send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { min_contribution: 6_000_000, ..terms }), &[&maker]), 1013);
+        let meta = send(&mut svm, build_update_ix(&maker, fundraiser.0, &terms), &[&maker]).unwrap();
+        assert_eq!(events(&meta), vec![Event::Updated(Updated {
+            fundraiser: fundraiser.0.to_bytes(),
+            maker: maker.pubkey().to_bytes(),
+            amount_to_raise: 40_000_000,
+            end_ts: start + 10 * DAY,
+            min_contribution: 1_000_000,
+            max_per_contributor: 5_000_000,
+            hard_cap: 50_000_000,
+            cap_kind: CAP_ABSOLUTE,
+            _padding: [0; 7],
+        })]);
+
+        let account = svm.get_account(&fundraiser.0).unwrap();
+        let state = FundRaiser::from_bytes(&account.data).unwrap();
+        assert_eq!(state.amount_to_raise(), 40_000_000);
+        assert_eq!(state.end_ts(), start + 10 * DAY);
+        assert_eq!(state.max_contribution_amount().unwrap(), 5_000_000);
+        assert_eq!(state.hard_cap(), 50_000_000);
+
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 5_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+
+        // Now only the deadline moves, and only outwards
+        assert_custom_error(send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { amount_to_raise: 20_000_000, ..terms }), &[&maker]), 1038);
+        assert_custom_error(send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { max_per_contributor: 10_000_000, ..terms }), &[&maker]), 1038);
+        assert_custom_error(send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { end_ts: start + 5 * DAY, ..terms }), &[&maker]), 1039);
+        send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { end_ts: start + 20 * DAY, ..terms }), &[&maker]).unwrap();
+
+        let account = svm.get_account(&fundraiser.0).unwrap();
+        assert_eq!(FundRaiser::from_bytes(&account.data).unwrap().end_ts(), start + 20 * DAY);
+
+        // An ended campaign cannot be revived
+        warp_to(&mut svm, start + 20 * DAY);
+        assert_custom_error(send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { end_ts: start + 40 * DAY, ..terms }), &[&maker]), 1006);
+        msg!("Campaign terms updated before contributions, deadline extended after");
+    }
+
+    #[test]
+    fn fundraiser_settles_at_stretch_tier() {
+        use crate::client::{settlement_address, FundRaiser, Settlement};
+        use crate::events::{Checked, Event, Settled, TrancheClaimed};
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 16_000_000);
+        let (bob, bob_ata) = funded_contributor(&mut svm, &payer, &mint, 16_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let maker_ata = spl_associated_token_account::get_associated_token_address(&maker.pubkey(), &mint);
+
+        let start = now(&svm);
+        let args = InitArgs {
+            cap_kind: CAP_ABSOLUTE,
+            max_per_contributor: 8_000_000,
+            hard_cap: 20_000_000,
+            flags: FLAG_STRETCH_GOALS,
+            ..InitArgs::new(10_000_000, start + 30 * DAY)
+        };
+        let initialize = |stretch_goals: &[u64]| with_stretch_goals(build_initialize_ix(&maker, mint, fundraiser.0, vault, &args), stretch_goals);
+
+        // Goals climb above the target, in order, up to the hard cap
+        assert_custom_error(send(&mut svm, initialize(&[10_000_000]), &[&maker]), 1040);
+        assert_custom_error(send(&mut svm, initialize(&[18_000_000, 15_000_000]), &[&maker]), 1040);
+        assert_custom_error(send(&mut svm, initialize(&[15_000_000, 25_000_000]), &[&maker]), 1040);
+        assert_custom_error(send(&mut svm, initialize(&[11, 12, 13, 14, 15].map(|goal| goal * 1_000_000)), &[&maker]), 1040);
+        send(&mut svm, initialize(&[15_000_000, 20_000_000]), &[&maker]).unwrap();
+
+        // Nor can the target later be raised past them
+        assert_custom_error(send(&mut svm, build_update_ix(&maker, fundraiser.0, &InitArgs { amount_to_raise: 16_000_000, ..args }), &[&maker]), 1040);
+
+        for (contributor, contributor_ata) in [(&alice, alice_ata), (&bob, bob_ata)] {
+            let ix = build_contribute_ix(contributor, mint, fundraiser.0, contributor_pda(&fundraiser.0, &contributor.pubkey()), contributor_ata, vault, 8_000_000);
+            send(&mut svm, ix, &[contributor]).unwrap();
+        }
+
+        let account = svm.get_account(&fundraiser.0).unwrap();
+        let state = FundRaiser::from_bytes(&account.data).unwrap();
+        assert_eq!(state.stretch_goal(0), Some(15_000_000));
+        assert_eq!(state.stretch_goal(2), None);
+        assert_eq!((state.tier_for(9_999_999), state.tier_for(10_000_000), state.tier_for(16_000_000), state.tier_for(20_000_000)), (0, 1, 2, 3));
+
+        // A milestone campaign settles on its first tranche claim
+        let milestone = fundraiser_pda(&maker.pubkey(), 1);
+        let milestone_vault = spl_associated_token_account::get_associated_token_address(&milestone.0, &mint);
+        let milestones = milestones_pda(&milestone.0);
+        let ix = build_initialize_ix(&maker, mint, milestone.0, milestone_vault, &InitArgs { campaign_id: 1, flags: FLAG_STRETCH_GOALS | FLAG_MILESTONES, ..args });
+        let ix = with_stretch_goals(with_milestones(ix, milestones, &[(10_000, start + 31 * DAY)]), &[12_000_000]);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        for (contributor, contributor_ata, amount) in [(&alice, alice_ata, 8_000_000), (&bob, bob_ata, 4_000_000)] {
+            let ix = build_contribute_ix(contributor, mint, milestone.0, contributor_pda(&milestone.0, &contributor.pubkey()), contributor_ata, milestone_vault, amount);
+            send(&mut svm, ix, &[contributor]).unwrap();
+        }
+
+        // 16 raised: base goal plus the first stretch goal
+        warp_to(&mut svm, start + 30 * DAY);
+        let settlement = settlement_address(&fundraiser.0).0;
+        let ix = build_checker_ix(&maker, mint, fundraiser.0, vault, maker_ata);
+        assert!(send(&mut svm, ix.clone(), &[&maker]).is_err());
+        assert_custom_error(send(&mut svm, with_accounts(ix.clone(), &[maker_ata]), &[&maker]), 1053);
+        let meta = send(&mut svm, with_accounts(ix, &[settlement]), &[&maker]).unwrap();
+        assert_eq!(events(&meta), vec![Event::Checked(Checked {
+            fundraiser: fundraiser.0.to_bytes(),
+            maker: maker.pubkey().to_bytes(),
+            maker_amount: 16_000_000,
+            fee_amount: 0,
+            tier: 2,
+            _padding: [0; 7],
+        })]);
+        assert_eq!(token_amount(&svm, &maker_ata), 16_000_000);
+
+        // The fundraiser is gone, the tier it settled at is not
+        assert!(svm.get_account(&fundraiser.0).map_or(true, |a| a.lamports == 0));
+        let account = svm.get_account(&settlement).unwrap();
+        let state = Settlement::from_bytes(&account.data).unwrap();
+        assert_eq!(Pubkey::new_from_array(state.fundraiser()), fundraiser.0);
+        assert_eq!((state.raised_amount(), state.tier()), (16_000_000, 2));
+
+        // 12 raised: past the milestone campaign's only stretch goal
+        warp_to(&mut svm, start + 31 * DAY);
+        let milestone_settlement = settlement_address(&milestone.0).0;
+        let ix = build_claim_tranche_ix(&maker, mint, milestone.0, milestones, milestone_vault, maker_ata);
+        let meta = send(&mut svm, with_accounts(ix, &[milestone_settlement]), &[&maker]).unwrap();
+        assert_eq!(events(&meta), vec![Event::Settled(Settled {
+            fundraiser: milestone.0.to_bytes(),
+            maker: maker.pubkey().to_bytes(),
+            raised_amount: 12_000_000,
+            tier: 2,
+            _padding: [0; 7],
+        }), Event::TrancheClaimed(TrancheClaimed {
+            fundraiser: milestone.0.to_bytes(),
+            maker: maker.pubkey().to_bytes(),
+            amount: 12_000_000,
+            fee_amount: 0,
+            remaining: 0,
+            tranche_index: 0,
+            _padding: [0; 7],
+        })]);
+        assert_eq!(token_amount(&svm, &maker_ata), 16_000_000 + 12_000_000);
+        let account = svm.get_account(&milestone_settlement).unwrap();
+        let state = Settlement::from_bytes(&account.data).unwrap();
+        assert_eq!((state.raised_amount(), state.tier()), (12_000_000, 2));
+        msg!("Checker and the first tranche claim settled the campaigns at their stretch tier");
+    }
+
+    #[test]
+    fn fundraiser_crank_refunds_failed_campaign() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        let keeper = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+        svm.airdrop(&keeper.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let start = now(&svm);
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, start + 30 * DAY));
+        send(&mut svm, ix, &[&maker]).unwrap();
+
+        let contributors: Vec<(Keypair, Pubkey)> = (0..3).map(|_| funded_contributor(&mut svm, &payer, &mint, 2_000_000)).collect();
+        let batch: Vec<(Pubkey, Pubkey, Pubkey)> = contributors.iter()
+            .map(|(contributor, contributor_ata)| (contributor_pda(&fundraiser.0, &contributor.pubkey()), contributor.pubkey(), *contributor_ata))
+            .collect();
+        for ((contributor, contributor_ata), (record, _, _)) in contributors.iter().zip(&batch) {
+            let ix = build_contribute_ix(contributor, mint, fundraiser.0, *record, *contributor_ata, vault, 2_000_000);
+            send(&mut svm, ix, &[contributor]).unwrap();
+        }
+
+        // Nothing to crank while the campaign can still succeed
+        let ix = build_crank_refund_ix(&keeper, &maker, mint, fundraiser.0, vault, &batch);
+        assert_custom_error(send(&mut svm, ix, &[&keeper]), 1005);
+
+        // The last backer refunds themselves first; the crank skips them
+        warp_to(&mut svm, start + 30 * DAY);
+        let (carol, carol_ata) = &contributors[2];
+        let ix = build_refund_ix(carol, &maker, mint, fundraiser.0, batch[2].0, *carol_ata, vault);
+        send(&mut svm, ix, &[carol]).unwrap();
+
+        // Tokens only go to the contributor's own token account: an entry pointing elsewhere is
+        // skipped, and the rest of the batch is still refunded
+        let swapped = [(batch[0].0, batch[0].1, batch[1].2), batch[1]];
+        let ix = build_crank_refund_ix(&keeper, &maker, mint, fundraiser.0, vault, &swapped);
+        let meta = send(&mut svm, ix, &[&keeper]).unwrap();
+        assert_eq!(events(&meta).len(), 1);
+        assert_eq!(token_amount(&svm, &batch[1].2), 2_000_000);
+        assert_eq!(contributor_amount(&svm, &batch[0].0), 2_000_000);
+        assert!(svm.get_account(&batch[1].0).map_or(true, |a| a.lamports == 0));
+
+        let rent = svm.minimum_balance_for_rent_exemption(crate::client::Contributor::LEN);
+        let bounty = rent / 10;
+        let alice_before = svm.get_balance(&batch[0].1).unwrap();
+        let keeper_before = svm.get_balance(&keeper.pubkey()).unwrap();
+        svm.expire_blockhash();
+        let ix = build_crank_refund_ix(&keeper, &maker, mint, fundraiser.0, vault, &batch);
+        let meta = send(&mut svm, ix, &[&keeper]).unwrap();
+        assert_eq!(events(&meta).len(), 1);
+
+        for (contributor_ata, (record, _, _)) in contributors.iter().map(|(_, ata)| ata).zip(&batch) {
+            assert_eq!(token_amount(&svm, contributor_ata), 2_000_000);
+            assert!(svm.get_account(record).map_or(true, |a| a.lamports == 0));
+        }
+        assert_eq!(token_amount(&svm, &vault), 0);
+        assert_eq!(svm.get_balance(&batch[0].1).unwrap(), alice_before + rent - bounty);
+        assert!(svm.get_balance(&keeper.pubkey()).unwrap() > keeper_before);
+        msg!("Keeper refunded the remaining backers");
+    }
+
+    #[test]
+    fn fundraiser_crank_refund_rejects_transfer_hook_mints() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        let keeper = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+        svm.airdrop(&keeper.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let token_program = spl_token_2022::ID;
+
+        let mint = create_hook_mint_2022(&mut svm, &payer, &Pubkey::new_unique());
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address_with_program_id(&fundraiser.0, &mint, &token_program);
+        let start = now(&svm);
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, start + 30 * DAY));
+        send(&mut svm, with_token_program(ix, token_program), &[&maker]).unwrap();
+
+        // The batch has no room for the hook's extra accounts, so the crank refuses the mint outright
+        warp_to(&mut svm, start + 30 * DAY);
+        let alice = Pubkey::new_unique();
+        let alice_ata = spl_associated_token_account::get_associated_token_address_with_program_id(&alice, &mint, &token_program);
+        let batch = [(contributor_pda(&fundraiser.0, &alice), alice, alice_ata)];
+        let ix = with_token_program(build_crank_refund_ix(&keeper, &maker, mint, fundraiser.0, vault, &batch), token_program);
+        assert_custom_error(send(&mut svm, ix, &[&keeper]), 1049);
+        msg!("Crank refunds leave transfer-hook mints to Refund");
+    }
+
+    #[test]
+    fn fundraiser_withdraw_with_cooldown_and_penalty() {
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&maker.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 4_000_000);
+        let fundraiser = fundraiser_pda(&maker.pubkey(), 0);
+        let vault = spl_associated_token_account::get_associated_token_address(&fundraiser.0, &mint);
+        let alice_record = contributor_pda(&fundraiser.0, &alice.pubkey());
+
+        // A penalty has to leave the contributor something back
+        let start = now(&svm);
+        let ix = build_initialize_ix(&maker, mint, fundraiser.0, vault, &InitArgs::new(30_000_000, start + 30 * DAY));
+        assert_custom_error(send(&mut svm, with_withdrawals(ix.clone(), DAY, 10_000), &[&maker]), 1045);
+
+        // A day of cooldown and a 5% penalty
+        send(&mut svm, with_withdrawals(ix, DAY, 500), &[&maker]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 3_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let withdraw = |amount| build_withdraw_ix(&alice, &maker, mint, fundraiser.0, alice_record, alice_ata, vault, amount);
+
+        assert_custom_error(send(&mut svm, withdraw(1_000_000), &[&alice]), 1044);
+
+        warp_to(&mut svm, start + DAY);
+        svm.expire_blockhash();
+        let meta = send(&mut svm, withdraw(1_000_000), &[&alice]).unwrap();
+        // The penalty stays in the vault while the campaign is open
+        assert_eq!(token_amount(&svm, &alice_ata), 1_950_000);
+        assert_eq!(token_amount(&svm, &maker_ata), 0);
+        assert_eq!(token_amount(&svm, &vault), 2_050_000);
+        assert_eq!(contributor_amount(&svm, &alice_record), 2_000_000);
+        let state = svm.get_account(&fundraiser.0).unwrap();
+        let state = crate::client::FundRaiser::from_bytes(&state.data).unwrap();
+        assert_eq!((state.current_amount(), state.penalty_amount(), state.raised_amount()), (2_000_000, 50_000, 2_050_000));
+        assert!(matches!(
+            events(&meta)[..],
+            [crate::events::Event::Withdrawn(crate::events::Withdrawn { amount: 1_000_000, penalty: 50_000, contributor_total: 2_000_000, .. })]
+        ));
+
+        // What stays in has to meet the minimum, and nobody takes out more than they put in
+        assert_custom_error(send(&mut svm, withdraw(1_500_000), &[&alice]), 1003);
+        assert_custom_error(send(&mut svm, withdraw(3_000_000), &[&alice]), 1046);
+
+        // A new contribution restarts the cooldown
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 1_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_custom_error(send(&mut svm, withdraw(3_000_000), &[&alice]), 1044);
+
+        // Withdrawing everything closes the record
+        warp_to(&mut svm, start + 2 * DAY);
+        svm.expire_blockhash();
+        send(&mut svm, withdraw(3_000_000), &[&alice]).unwrap();
+        assert!(svm.get_account(&alice_record).map_or(true, |a| a.lamports == 0));
+        assert_eq!(token_amount(&svm, &alice_ata), 950_000 + 2_850_000);
+        assert_eq!(token_amount(&svm, &vault), 200_000);
+
+        // Nothing comes back out once the campaign is over
+        svm.expire_blockhash();
+        let ix = build_contribute_ix(&alice, mint, fundraiser.0, alice_record, alice_ata, vault, 1_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        warp_to(&mut svm, start + 30 * DAY);
+        svm.expire_blockhash();
+        assert_custom_error(send(&mut svm, withdraw(1_000_000), &[&alice]), 1006);
+
+        // A failed campaign shares the kept penalties out with the refunds, and only then can be closed
+        let close = |fundraiser, vault| build_close_ix(&maker, mint, fundraiser, vault, maker_ata);
+        assert_custom_error(send(&mut svm, close(fundraiser.0, vault), &[&maker]), 1050);
+        let ix = build_refund_ix(&alice, &maker, mint, fundraiser.0, alice_record, alice_ata, vault);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(token_amount(&svm, &alice_ata), 4_000_000);
+        assert_eq!(token_amount(&svm, &maker_ata), 0);
+        send(&mut svm, close(fundraiser.0, vault), &[&maker]).unwrap();
+        assert!(svm.get_account(&fundraiser.0).map_or(true, |a| a.lamports == 0));
+        assert!(svm.get_account(&vault).map_or(true, |a| a.lamports == 0));
+
+        // A successful one pays them to the maker, counted toward the target
+        let funded = fundraiser_pda(&maker.pubkey(), 2);
+        let funded_vault = spl_associated_token_account::get_associated_token_address(&funded.0, &mint);
+        let funded_record = contributor_pda(&funded.0, &alice.pubkey());
+        let funded_start = now(&svm);
+        let args = InitArgs { campaign_id: 2, max_per_contributor: 10_000, ..InitArgs::new(3_000_000, funded_start + DAY) };
+        let ix = build_initialize_ix(&maker, mint, funded.0, funded_vault, &args);
+        send(&mut svm, with_withdrawals(ix, 0, 5_000), &[&maker]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, funded.0, funded_record, alice_ata, funded_vault, 3_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_withdraw_ix(&alice, &maker, mint, funded.0, funded_record, alice_ata, funded_vault, 2_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, funded.0, funded_record, alice_ata, funded_vault, 1_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        assert_eq!(contributor_amount(&svm, &funded_record), 2_000_000);
+        assert_eq!(token_amount(&svm, &funded_vault), 3_000_000);
+        warp_to(&mut svm, funded_start + DAY);
+        send(&mut svm, build_checker_ix(&maker, mint, funded.0, funded_vault, maker_ata), &[&maker]).unwrap();
+        assert_eq!(token_amount(&svm, &maker_ata), 3_000_000);
+
+        // With every backer gone, the maker closes the failed campaign and reclaims the penalties
+        let (bob, bob_ata) = funded_contributor(&mut svm, &payer, &mint, 1_000_000);
+        let abandoned = fundraiser_pda(&maker.pubkey(), 3);
+        let abandoned_vault = spl_associated_token_account::get_associated_token_address(&abandoned.0, &mint);
+        let bob_record = contributor_pda(&abandoned.0, &bob.pubkey());
+        let abandoned_start = now(&svm);
+        let args = InitArgs { campaign_id: 3, ..InitArgs::new(30_000_000, abandoned_start + DAY) };
+        let ix = build_initialize_ix(&maker, mint, abandoned.0, abandoned_vault, &args);
+        send(&mut svm, with_withdrawals(ix, 0, 500), &[&maker]).unwrap();
+        let ix = build_contribute_ix(&bob, mint, abandoned.0, bob_record, bob_ata, abandoned_vault, 1_000_000);
+        send(&mut svm, ix, &[&bob]).unwrap();
+        let ix = build_withdraw_ix(&bob, &maker, mint, abandoned.0, bob_record, bob_ata, abandoned_vault, 1_000_000);
+        send(&mut svm, ix, &[&bob]).unwrap();
+        assert_eq!(token_amount(&svm, &abandoned_vault), 50_000);
+        assert_custom_error(send(&mut svm, close(abandoned.0, abandoned_vault), &[&maker]), 1005);
+        warp_to(&mut svm, abandoned_start + DAY);
+        let meta = send(&mut svm, close(abandoned.0, abandoned_vault), &[&maker]).unwrap();
+        assert_eq!(token_amount(&svm, &maker_ata), 3_050_000);
+        assert!(svm.get_account(&abandoned.0).map_or(true, |a| a.lamports == 0));
+        assert!(svm.get_account(&abandoned_vault).map_or(true, |a| a.lamports == 0));
+        assert!(matches!(events(&meta)[..], [crate::events::Event::Closed(crate::events::Closed { amount: 50_000, .. })]));
+
+        // Campaigns without withdrawal terms keep contributions locked in
+        let locked = fundraiser_pda(&maker.pubkey(), 1);
+        let locked_vault = spl_associated_token_account::get_associated_token_address(&locked.0, &mint);
+        let locked_record = contributor_pda(&locked.0, &alice.pubkey());
+        let args = InitArgs { campaign_id: 1, ..InitArgs::new(30_000_000, now(&svm) + 30 * DAY) };
+        send(&mut svm, build_initialize_ix(&maker, mint, locked.0, locked_vault, &args), &[&maker]).unwrap();
+        let ix = build_contribute_ix(&alice, mint, locked.0, locked_record, alice_ata, locked_vault, 1_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_withdraw_ix(&alice, &maker, mint, locked.0, locked_record, alice_ata, locked_vault, 1_000_000);
+        assert_custom_error(send(&mut svm, ix, &[&alice]), 1043);
+        msg!("Withdrawals honour the cooldown and keep the penalty in the vault");
+    }
+
+    #[test]
+    #[cfg_attr(feature = "logging", ignore = "budgets are measured with logging compiled out")]
+    fn fundraiser_compute_unit_budgets() {
+        // Fixed ceilings per instruction, with logging compiled out; a change that pushes one over
+        // has to justify the extra compute and raise it deliberately
+        fn assert_within_budget(meta: &litesvm::types::TransactionMetadata, budget: u64, instruction: &str) {
+            assert!(
+                meta.compute_units_consumed <= budget,
+                "{instruction} used {} CU, over its budget of {budget}",
+                meta.compute_units_consumed,
+            );
+        }
+
+        let (mut svm, payer) = setup();
+        let maker = Keypair::new();
+        svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+
+        let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+        let (alice, alice_ata) = funded_contributor(&mut svm, &payer, &mint, 5_000_000);
+        let (bob, bob_ata) = funded_contributor(&mut svm, &payer, &mint, 1_000_000);
+        let (carol, carol_ata) = funded_contributor(&mut svm, &payer, &mint, 1_000_000);
+        let (dave, dave_ata) = funded_contributor(&mut svm, &payer, &mint, 1_000_000);
+        let keeper = Keypair::new();
+        svm.airdrop(&keeper.pubkey(), LAMPORTS_PER_SOL).unwrap();
+        let start = now(&svm);
+
+        // Campaign 0 succeeds, campaign 1 falls short and is refunded, campaign 2 releases a governed
+        // milestone to the maker
+        let funded = fundraiser_pda(&maker.pubkey(), 0);
+        let funded_vault = spl_associated_token_account::get_associated_token_address(&funded.0, &mint);
+        let alice_record = contributor_pda(&funded.0, &alice.pubkey());
+        let args = InitArgs { max_per_contributor: 10_000, ..InitArgs::new(3_000_000, start + 30 * DAY) };
+        let meta = send(&mut svm, build_initialize_ix(&maker, mint, funded.0, funded_vault, &args), &[&maker]).unwrap();
+        assert_within_budget(&meta, 60_000, "Initialize");
+
+        let failed = fundraiser_pda(&maker.pubkey(), 1);
+        let failed_vault = spl_associated_token_account::get_associated_token_address(&failed.0, &mint);
+        let bob_record = contributor_pda(&failed.0, &bob.pubkey());
+        let args = InitArgs { campaign_id: 1, ..InitArgs::new(30_000_000, start + 30 * DAY) };
+        send(&mut svm, build_initialize_ix(&maker, mint, failed.0, failed_vault, &args), &[&maker]).unwrap();
+
+        // Creating the record pays for the bump search, later contributions use the stored bump
+        let ix = build_contribute_ix(&alice, mint, funded.0, alice_record, alice_ata, funded_vault, 2_000_000);
+        let meta = send(&mut svm, ix, &[&alice]).unwrap();
+        assert_within_budget(&meta, 30_000, "Contribute (new record)");
+        let ix = build_contribute_ix(&alice, mint, funded.0, alice_record, alice_ata, funded_vault, 1_000_000);
+        let meta = send(&mut svm, ix, &[&alice]).unwrap();
+        assert_within_budget(&meta, 20_000, "Contribute");
+        let batch: Vec<(Pubkey, Pubkey, Pubkey)> = [(&bob, bob_ata), (&carol, carol_ata), (&dave, dave_ata)].into_iter()
+            .map(|(contributor, contributor_ata)| {
+                let record = contributor_pda(&failed.0, &contributor.pubkey());
+                let ix = build_contribute_ix(contributor, mint, failed.0, record, contributor_ata, failed_vault, 1_000_000);
+                send(&mut svm, ix, &[contributor]).unwrap();
+                (record, contributor.pubkey(), contributor_ata)
+            })
+            .collect();
+
+        let governed = fundraiser_pda(&maker.pubkey(), 2);
+        let governed_vault = spl_associated_token_account::get_associated_token_address(&governed.0, &mint);
+        let governed_milestones = milestones_pda(&governed.0);
+        let governed_record = contributor_pda(&governed.0, &alice.pubkey());
+        let args = InitArgs {
+            campaign_id: 2,
+            max_per_contributor: 5_000_000,
+            cap_kind: CAP_ABSOLUTE,
+            flags: FLAG_MILESTONES | FLAG_GOVERNANCE,
+            ..InitArgs::new(1_000_000, start + 30 * DAY)
+        };
+        let ix = with_milestones(build_initialize_ix(&maker, mint, governed.0, governed_vault, &args), governed_milestones, &[(10_000, start + 31 * DAY)]);
+        let ix = with_withdrawals(with_governance(ix, 5_000, 5_000), 0, 500);
+        send(&mut svm, ix, &[&maker]).unwrap();
+        let meta = send(&mut svm, build_update_ix(&maker, governed.0, &InitArgs { amount_to_raise: 1_500_000, ..args }), &[&maker]).unwrap();
+        assert_within_budget(&meta, 10_000, "Update");
+        let ix = build_contribute_ix(&alice, mint, governed.0, governed_record, alice_ata, governed_vault, 2_000_000);
+        send(&mut svm, ix, &[&alice]).unwrap();
+        let ix = build_withdraw_ix(&alice, &maker, mint, governed.0, governed_record, alice_ata, governed_vault, 500_000);
+        let meta = send(&mut svm, ix, &[&alice]).unwrap();
+        assert_within_budget(&meta, 25_000, "Withdraw");
+
+        warp_to(&mut svm, start + 30 * DAY);
+        let maker_ata = spl_associated_token_account::get_associated_token_address(&maker.pubkey(), &mint);
+        let meta = send(&mut svm, build_checker_ix(&maker, mint, funded.0, funded_vault, maker_ata), &[&maker]).unwrap();
+        assert_within_budget(&meta, 80_000, "Checker");
+
+        let ix = build_refund_ix(&bob, &maker, mint, failed.0, bob_record, bob_ata, failed_vault);
+        let meta = send(&mut svm, ix, &[&bob]).unwrap();
+        assert_within_budget(&meta, 20_000, "Refund");
+
+        // The crank pays a fixed cost for the campaign, then about a Refund per contributor
+        let ix = build_crank_refund_ix(&keeper, &maker, mint, failed.0, failed_vault, &batch[1..]);
+        let meta = send(&mut svm, ix, &[&keeper]).unwrap();
+        assert_within_budget(&meta, 10_000 + 2 * 15_000, "CrankRefund (2 contributors)");
+
+        let ix = build_vote_ix(&alice, governed.0, governed_record, true);
+        let meta = send(&mut svm, ix, &[&alice]).unwrap();
+        assert_within_budget(&meta, 5_000, "Vote");
+
+        // Migrating a record searches for its bump once
+        downgrade_layout(&mut svm, &alice_record, 2, crate::client::Contributor::LEN_V2);
+        let ix = with_readonly_accounts(build_migrate_ix(&payer, alice_record), &[funded.0, alice.pubkey()]);
+        let meta = send(&mut svm, ix, &[&payer]).unwrap();
+        assert_within_budget(&meta, 10_000, "Migrate");
+
+        warp_to(&mut svm, start + 31 * DAY);
+        let ix = build_claim_tranche_ix(&maker, mint, governed.0, governed_milestones, governed_vault, maker_ata);
+        let meta = send(&mut svm, ix, &[&maker]).unwrap();
+        assert_within_budget(&meta, 60_000, "ClaimTranche");
+
+        // Creating the config checks the upgrade authority, later changes only the admin
+        set_upgrade_authority(&mut svm, &maker.pubkey());
+        let meta = send(&mut svm, build_set_config_ix(&maker, 250, maker.pubkey(), maker.pubkey()), &[&maker]).unwrap();
+        assert_within_budget(&meta, 10_000, "SetConfig (create)");
+        let meta = send(&mut svm, build_set_config_ix(&maker, 100, maker.pubkey(), maker.pubkey()), &[&maker]).unwrap();
+        assert_within_budget(&meta, 5_000, "SetConfig");
+        msg!("Instructions stay within their compute budgets");
+    }
+
+    // Random sequences of Initialize/Contribute/Checker/Refund, clock warps and account substitutions
+    // by an attacker, checking the vault's bookkeeping after every step
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        const CONTRIBUTORS: usize = 3;
+        const FUNDING: u64 = 10_000_000;
+
+        #[derive(Clone, Debug)]
+        enum Action {
+            // The maker opens the campaign; sent again while it is live, it must be turned away
+            Initialize,
+            Contribute { who: usize, amount: u64 },
+            Refund { who: usize },
+            Checker,
+            Warp { hours: i64 },
+            // Mallory signs for someone else's record, routing the refund to her token account
+            RefundAs { who: usize },
+            // Mallory poses as the maker to collect the raise
+            CheckerAs,
+            // A contributor refunds out of Mallory's token account posing as the vault
+            SwapVault { who: usize },
+        }
+
+        fn action() -> impl Strategy<Value = Action> {
+            prop_oneof![
+                1 => Just(Action::Initialize),
+                4 => (0..CONTRIBUTORS, 0..4_000_000u64).prop_map(|(who, amount)| Action::Contribute { who, amount }),
+                2 => (0..CONTRIBUTORS).prop_map(|who| Action::Refund { who }),
+                1 => Just(Action::Checker),
+                2 => (1..=20 * 24i64).prop_map(|hours| Action::Warp { hours }),
+                1 => (0..CONTRIBUTORS).prop_map(|who| Action::RefundAs { who }),
+                1 => Just(Action::CheckerAs),
+                1 => (0..CONTRIBUTORS).prop_map(|who| Action::SwapVault { who }),
+            ]
+        }
+
+        // Campaign terms drawn per case, kept valid so the campaign can open
+        #[derive(Clone, Debug)]
+        struct Terms {
+            amount_to_raise: u64,
+            min_contribution: u64,
+            max_per_contributor_bps: u64,
+            duration_days: i64,
+            flags: u8,
+        }
+
+        fn terms() -> impl Strategy<Value = Terms> {
+            (
+                1_000_000..12_000_000u64,
+                1..=100_000u64,
+                1_000..=10_000u64,
+                1..=30i64,
+                prop::sample::select(vec![0, FLAG_PARTIAL_FILL, FLAG_END_ON_TARGET, FLAG_PARTIAL_FILL | FLAG_END_ON_TARGET]),
+            ).prop_map(|(amount_to_raise, min_contribution, max_per_contributor_bps, duration_days, flags)| Terms {
+                amount_to_raise,
+                min_contribution,
+                max_per_contributor_bps,
+                duration_days,
+                flags,
+            })
+        }
+
+        struct Contributor {
+            keypair: Keypair,
+            ata: Pubkey,
+            record: Pubkey,
+        }
+
+        struct World {
+            svm: LiteSVM,
+            mint: Pubkey,
+            maker: Keypair,
+            maker_ata: Pubkey,
+            mallory: Keypair,
+            mallory_ata: Pubkey,
+            fundraiser: Pubkey,
+            vault: Pubkey,
+            contributors: Vec<Contributor>,
+            terms: Terms,
+            end_ts: i64,
+            initialized: bool,
+        }
+
+        impl World {
+            fn new(terms: Terms) -> Self {
+                let (mut svm, payer) = setup();
+                let maker = Keypair::new();
+                let mallory = Keypair::new();
+                svm.airdrop(&maker.pubkey(), 2 * LAMPORTS_PER_SOL).unwrap();
+                svm.airdrop(&mallory.pubkey(), LAMPORTS_PER_SOL).unwrap();
+
+                let mint = CreateMint::new(&mut svm, &payer).decimals(6).authority(&payer.pubkey()).send().unwrap();
+                let maker_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&maker.pubkey()).send().unwrap();
+                let mallory_ata = CreateAssociatedTokenAccount::new(&mut svm, &payer, &mint).owner(&mallory.pubkey()).send().unwrap();
+                let fundraiser = fundraiser_pda(&maker.pubkey(), 0).0;
+                let vault = spl_associated_token_account::get_associated_token_address(&fundraiser, &mint);
+                let contributors = (0..CONTRIBUTORS).map(|_| {
+                    let (keypair, ata) = funded_contributor(&mut svm, &payer, &mint, FUNDING);
+                    let record = contributor_pda(&fundraiser, &keypair.pubkey());
+                    Contributor { keypair, ata, record }
+                }).collect();
+
+                // The campaign opens with the first Initialize action, so everything before it bounces
+                let end_ts = now(&svm) + terms.duration_days * DAY;
+
+                Self { svm, mint, maker, maker_ata, mallory, mallory_ata, fundraiser, vault, contributors, terms, end_ts, initialized: false }
+            }
+
+            fn initialize_ix(&self) -> Instruction {
+                let args = InitArgs {
+                    min_contribution: self.terms.min_contribution,
+                    max_per_contributor: self.terms.max_per_contributor_bps,
+                    flags: self.terms.flags,
+                    ..InitArgs::new(self.terms.amount_to_raise, self.end_ts)
+                };
+                build_initialize_ix(&self.maker, self.mint, self.fundraiser, self.vault, &args)
+            }
+
+            // Rejections are part of the game; only the invariants decide whether a step went wrong
+            fn apply(&mut self, action: &Action) {
+                self.svm.expire_blockhash();
+                let (ix, signer) = match *action {
+                    Action::Initialize => {
+                        // A settled campaign's address could host a fresh one, which these invariants don't track
+                        if self.initialized && self.raised().is_none() {
+                            return;
+                        }
+                        let ix = self.initialize_ix();
+                        if send(&mut self.svm, ix, &[&self.maker]).is_ok() {
+                            self.initialized = true;
+                        }
+                        return;
+                    }
+                    Action::Contribute { who, amount } => {
+                        let c = &self.contributors[who];
+                        (build_contribute_ix(&c.keypair, self.mint, self.fundraiser, c.record, c.ata, self.vault, amount), &c.keypair)
+                    }
+                    Action::Refund { who } => {
+                        let c = &self.contributors[who];
+                        (build_refund_ix(&c.keypair, &self.maker, self.mint, self.fundraiser, c.record, c.ata, self.vault), &c.keypair)
+                    }
+                    Action::Checker => (build_checker_ix(&self.maker, self.mint, self.fundraiser, self.vault, self.maker_ata), &self.maker),
+                    Action::Warp { hours } => {
+                        let now = now(&self.svm);
+                        warp_to(&mut self.svm, now + hours * 3_600);
+                        return;
+                    }
+                    Action::RefundAs { who } => {
+                        let c = &self.contributors[who];
+                        (build_refund_ix(&self.mallory, &self.maker, self.mint, self.fundraiser, c.record, self.mallory_ata, self.vault), &self.mallory)
+                    }
+                    Action::CheckerAs => (build_checker_ix(&self.mallory, self.mint, self.fundraiser, self.vault, self.mallory_ata), &self.mallory),
+                    Action::SwapVault { who } => {
+                        let c = &self.contributors[who];
+                        (build_refund_ix(&c.keypair, &self.maker, self.mint, self.fundraiser, c.record, c.ata, self.mallory_ata), &c.keypair)
+                    }
+                };
+                let _ = send(&mut self.svm, ix, &[signer]);
+            }
+
+            // Closed accounts read as empty
+            fn token_balance(&self, token_account: &Pubkey) -> u64 {
+                self.svm.get_account(token_account)
+                    .filter(|account| account.data.len() >= 72)
+                    .map_or(0, |account| u64::from_le_bytes(account.data[64..72].try_into().unwrap()))
+            }
+
+            fn recorded(&self, record: &Pubkey) -> u64 {
+                self.svm.get_account(record)
+                    .and_then(|account| crate::client::Contributor::from_bytes(&account.data).map(|state| state.amount()).ok())
+                    .unwrap_or(0)
+            }
+
+            // `current_amount` while the campaign is live, `None` before Initialize and once Checker closed it
+            fn raised(&self) -> Option<u64> {
+                self.svm.get_account(&self.fundraiser)
+                    .and_then(|account| crate::client::FundRaiser::from_bytes(&account.data).map(|state| state.current_amount()).ok())
+            }
+
+            fn check_invariants(&self) -> Result<(), TestCaseError> {
+                let recorded: u64 = self.contributors.iter().map(|c| self.recorded(&c.record)).sum();
+                let vault = self.token_balance(&self.vault);
+                let maker = self.token_balance(&self.maker_ata);
+
+                // Contributors get back exactly what is still recorded for them
+                for c in &self.contributors {
+                    prop_assert_eq!(self.token_balance(&c.ata) + self.recorded(&c.record), FUNDING);
+                }
+                match self.raised() {
+                    // The vault holds exactly the unrefunded contributions and the maker has nothing yet
+                    Some(raised) => {
+                        prop_assert_eq!(vault, recorded);
+                        prop_assert_eq!(raised, recorded);
+                        prop_assert_eq!(maker, 0);
+                    }
+                    // Settled: everything still recorded went to the maker
+                    None => {
+                        prop_assert_eq!(vault, 0);
+                        prop_assert_eq!(maker, recorded);
+                    }
+                }
+                // Tokens only ever move between contributors, the vault and the maker
+                prop_assert_eq!(self.token_balance(&self.mallory_ata), 0);
+                Ok(())
+            }
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig { cases: 48, ..ProptestConfig::default() })]
+
+            #[test]
+            fn fundraiser_invariants_hold(
+                terms in terms(),
+                actions in prop::collection::vec(action(), 1..32),
+            ) {
+                let mut world = World::new(terms);
+                world.check_invariants()?;
+                for action in &actions {
+                    world.apply(action);
+                    world.check_invariants()?;
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,243 @@
+use pinocchio::{
+    account_info::AccountInfo, cpi::slice_invoke_signed, instruction::{AccountMeta, Instruction, Signer}, program_error::ProgramError, pubkey::Pubkey, ProgramResult
+};
+
+use crate::error::FundRaiserError;
+
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pinocchio_pubkey::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+pub const MINT_BASE_LEN: usize = 82;
+const TOKEN_ACCOUNT_BASE_LEN: usize = 165;
+// Token-2022 extensions start after the base account and its one-byte account type
+const EXTENSIONS_OFFSET: usize = TOKEN_ACCOUNT_BASE_LEN + 1;
+const TRANSFER_FEE_AMOUNT_EXTENSION: u16 = 2;
+const TRANSFER_HOOK_EXTENSION: u16 = 14;
+
+const MINT_TO: u8 = 7;
+const BURN: u8 = 8;
+const CLOSE_ACCOUNT: u8 = 9;
+const TRANSFER_CHECKED: u8 = 12;
+const TRANSFER_FEE_EXTENSION: u8 = 26;
+const HARVEST_WITHHELD_TOKENS_TO_MINT: u8 = 4;
+const INITIALIZE_MINT_2: u8 = 20;
+
+/// Only legacy SPL Token and Token-2022 are accepted.
+pub fn check_token_program(token_program: &AccountInfo) -> ProgramResult {
+    if token_program.key() != &pinocchio_token::ID && token_program.key() != &TOKEN_2022_PROGRAM_ID {
+        return Err(FundRaiserError::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// Returns the decimals of an initialized mint owned by `token_program`.
+pub fn mint_decimals(mint: &AccountInfo, token_program: &AccountInfo) -> Result<u8, ProgramError> {
+    if mint.owner() != token_program.key() {
+        return Err(FundRaiserError::InvalidTokenProgram.into());
+    }
+    let data = mint.try_borrow_data()?;
+    // mint_authority (36) | supply (8) | decimals (1) | is_initialized (1) | ...
+    if data.len() < MINT_BASE_LEN || data[45] == 0 {
+        return Err(ProgramError::UninitializedAccount);
+    }
+    Ok(data[44])
+}
+
+/// Reads the balance of a token account owned by `token_program`, with or without extensions.
+pub fn token_account_amount(account: &AccountInfo, token_program: &AccountInfo) -> Result<u64, ProgramError> {
+    if account.owner() != token_program.key() {
+        return Err(FundRaiserError::InvalidTokenProgram.into());
+    }
+    let data = account.try_borrow_data()?;
+    // mint (32) | owner (32) | amount (8) | ...
+    if data.len() < TOKEN_ACCOUNT_BASE_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(u64::from_le_bytes(data[64..72].try_into().unwrap()))
+}
+
+/// `account` must be a `token_program` account for `mint` owned by `owner`.
+pub fn check_token_account(account: &AccountInfo, owner: &Pubkey, mint: &Pubkey, token_program: &AccountInfo) -> ProgramResult {
+    if account.owner() != token_program.key() {
+        return Err(FundRaiserError::InvalidTokenAccount.into());
+    }
+    let data = account.try_borrow_data()?;
+    // mint (32) | owner (32) | ...
+    if data.len() < TOKEN_ACCOUNT_BASE_LEN || data[0..32] != mint[..] || data[32..64] != owner[..] {
+        return Err(FundRaiserError::InvalidTokenAccount.into());
+    }
+    Ok(())
+}
+
+// Value of a Token-2022 extension in a mint or token account's TLV data, if present
+fn extension(data: &[u8], extension_type: u16) -> Option<&[u8]> {
+    let mut offset = EXTENSIONS_OFFSET;
+    while offset + 4 <= data.len() {
+        let found_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let value = offset + 4;
+        if value + length > data.len() {
+            return None;
+        }
+        if found_type == extension_type {
+            return Some(&data[value..value + length]);
+        }
+        offset = value + length;
+    }
+    None
+}
+
+/// Transfer fees withheld in a Token-2022 account (0 for accounts without the extension).
+fn withheld_amount(account: &AccountInfo) -> Result<u64, ProgramError> {
+    let data = account.try_borrow_data()?;
+    Ok(match extension(&data, TRANSFER_FEE_AMOUNT_EXTENSION) {
+        Some(value) if value.len() >= 8 => u64::from_le_bytes(value[0..8].try_into().unwrap()),
+        _ => 0,
+    })
+}
+
+/// Whether `mint` is a Token-2022 mint whose transfers invoke a transfer-hook program.
+pub fn has_transfer_hook(mint: &AccountInfo) -> Result<bool, ProgramError> {
+    if mint.owner() != &TOKEN_2022_PROGRAM_ID {
+        return Ok(false);
+    }
+    let data = mint.try_borrow_data()?;
+    // authority (32) | program_id (32), all zeroes when no hook program is set
+    Ok(matches!(extension(&data, TRANSFER_HOOK_EXTENSION), Some(value) if value.len() >= 64 && value[32..64] != [0; 32]))
+}
+
+/// `TransferChecked` against either token program. `extra_accounts` are forwarded as-is so
+/// transfer-hook mints receive their hook program and extra account metas.
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_checked(
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    mint: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    decimals: u8,
+    extra_accounts: &[AccountInfo],
+    signers: &[Signer],
+) -> ProgramResult {
+    let mut data = [0u8; 10];
+    data[0] = TRANSFER_CHECKED;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+    data[9] = decimals;
+
+    let mut metas = Vec::with_capacity(4 + extra_accounts.len());
+    metas.push(AccountMeta::writable(from.key()));
+    metas.push(AccountMeta::readonly(mint.key()));
+    metas.push(AccountMeta::writable(to.key()));
+    metas.push(AccountMeta::readonly_signer(authority.key()));
+
+    let mut infos = Vec::with_capacity(4 + extra_accounts.len());
+    infos.extend_from_slice(&[from, mint, to, authority]);
+
+    for account in extra_accounts {
+        metas.push(AccountMeta::new(account.key(), account.is_writable(), false));
+        infos.push(account);
+    }
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &metas,
+        data: &data,
+    };
+    slice_invoke_signed(&instruction, &infos, signers)
+}
+
+/// Closes `account` to `destination`. Token-2022 accounts holding withheld transfer fees
+/// cannot be closed, so those fees are harvested to the mint first.
+pub fn close_account(
+    token_program: &AccountInfo,
+    account: &AccountInfo,
+    mint: &AccountInfo,
+    destination: &AccountInfo,
+    authority: &AccountInfo,
+    signers: &[Signer],
+) -> ProgramResult {
+    if token_program.key() == &TOKEN_2022_PROGRAM_ID && withheld_amount(account)? > 0 {
+        let instruction = Instruction {
+            program_id: token_program.key(),
+            accounts: &[AccountMeta::writable(mint.key()), AccountMeta::writable(account.key())],
+            data: &[TRANSFER_FEE_EXTENSION, HARVEST_WITHHELD_TOKENS_TO_MINT],
+        };
+        slice_invoke_signed(&instruction, &[mint, account], &[])?;
+    }
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &[
+            AccountMeta::writable(account.key()),
+            AccountMeta::writable(destination.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        data: &[CLOSE_ACCOUNT],
+    };
+    slice_invoke_signed(&instruction, &[account, destination, authority], signers)
+}
+
+/// `InitializeMint2` for a freshly allocated mint, without a freeze authority.
+pub fn initialize_mint(token_program: &AccountInfo, mint: &AccountInfo, decimals: u8, mint_authority: &Pubkey) -> ProgramResult {
+    let mut data = [0u8; 35];
+    data[0] = INITIALIZE_MINT_2;
+    data[1] = decimals;
+    data[2..34].copy_from_slice(mint_authority);
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &[AccountMeta::writable(mint.key())],
+        data: &data,
+    };
+    slice_invoke_signed(&instruction, &[mint], &[])
+}
+
+/// `MintTo` against either token program.
+pub fn mint_to(
+    token_program: &AccountInfo,
+    mint: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    let mut data = [0u8; 9];
+    data[0] = MINT_TO;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &[
+            AccountMeta::writable(mint.key()),
+            AccountMeta::writable(to.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        data: &data,
+    };
+    slice_invoke_signed(&instruction, &[mint, to, authority], signers)
+}
+
+/// `Burn` against either token program.
+pub fn burn(
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    mint: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    signers: &[Signer],
+) -> ProgramResult {
+    let mut data = [0u8; 9];
+    data[0] = BURN;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &[
+            AccountMeta::writable(from.key()),
+            AccountMeta::writable(mint.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        data: &data,
+    };
+    slice_invoke_signed(&instruction, &[from, mint, authority], signers)
+}
@@ -0,0 +1,62 @@
+use pinocchio::{
+    account_info::AccountInfo, instruction::{Seed, Signer}, program_error::ProgramError, pubkey::find_program_address, sysvars::{rent::Rent, Sysvar}, ProgramResult
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::{error::FundRaiserError, state::settlement::Settlement};
+
+/// Moves all lamports of a program-owned `account` to `destination` and closes it.
+pub fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    {
+        let mut destination_lamports = destination.try_borrow_mut_lamports()?;
+        *destination_lamports = destination_lamports
+            .checked_add(account.lamports())
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+    }
+    account.close()
+}
+
+/// Debits `amount` lamports from a program-owned `from` and credits them to `to`.
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+    {
+        let mut from_lamports = from.try_borrow_mut_lamports()?;
+        *from_lamports = from_lamports
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+    }
+    let mut to_lamports = to.try_borrow_mut_lamports()?;
+    *to_lamports = to_lamports
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Lamports held by the native SOL vault on top of its rent-exempt reserve.
+pub fn native_vault_balance(vault: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(vault.lamports().saturating_sub(Rent::get()?.minimum_balance(0)))
+}
+
+/// Creates the `[b"settlement", fundraiser]` record of a stretch-goal campaign, paid for by the maker.
+pub fn create_settlement(maker: &AccountInfo, fundraiser: &AccountInfo, settlement: &AccountInfo, raised_amount: u64, tier: u8) -> ProgramResult {
+    let (expected, bump) = find_program_address(&[b"settlement", fundraiser.key().as_ref()], &crate::ID);
+    if settlement.key() != &expected {
+        return Err(FundRaiserError::InvalidSettlement.into());
+    }
+
+    let bump_arr = [bump];
+    let seed = [Seed::from(b"settlement"), Seed::from(fundraiser.key()), Seed::from(&bump_arr)];
+    CreateAccount {
+        from: maker,
+        to: settlement,
+        lamports: Rent::get()?.minimum_balance(Settlement::LEN),
+        space: Settlement::LEN as u64,
+        owner: &crate::ID,
+    }.invoke_signed(&[Signer::from(&seed)])?;
+
+    let settlement_state = Settlement::from_account_info(settlement)?;
+    settlement_state.set_fundraiser(fundraiser.key());
+    settlement_state.set_raised_amount(&raised_amount);
+    settlement_state.set_tier(tier);
+    settlement_state.bump = bump;
+    Ok(())
+}
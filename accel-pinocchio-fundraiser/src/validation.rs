@@ -0,0 +1,283 @@
+use pinocchio::{account_info::AccountInfo, pubkey::{create_program_address, find_program_address}, ProgramResult};
+
+use crate::{constants::{BASIS_POINTS_SCALER, BPF_LOADER_UPGRADEABLE_ID}, error::FundRaiserError, state::{config::Config, contributor::Contributor, fundraiser::{ContributionCapKind, FundRaiser, FundRaiserPhase, FundRaiserStatus}, milestones::Milestones}, token::check_token_program};
+
+/// The fundraiser must be a program-owned account with the current `FundRaiser` layout;
+/// unversioned or older layouts have to go through `Migrate` first.
+pub fn check_fundraiser_account(fundraiser: &AccountInfo) -> ProgramResult {
+    if fundraiser.owner() != &crate::ID {
+        return Err(FundRaiserError::InvalidFundraiser.into());
+    }
+    if matches!(fundraiser.data_len(), FundRaiser::LEGACY_LEN | FundRaiser::LEN_V1 | FundRaiser::LEN_V2) {
+        return Err(FundRaiserError::InvalidAccountVersion.into());
+    }
+    if fundraiser.data_len() != FundRaiser::LEN {
+        return Err(FundRaiserError::InvalidFundraiser.into());
+    }
+    let fundraiser_state = FundRaiser::from_account_info(fundraiser)?;
+    if fundraiser_state.discriminator() != FundRaiser::DISCRIMINATOR {
+        return Err(FundRaiserError::InvalidFundraiser.into());
+    }
+    if fundraiser_state.version() != FundRaiser::VERSION {
+        return Err(FundRaiserError::InvalidAccountVersion.into());
+    }
+    Ok(())
+}
+
+/// A program slot must hold that very program, so a substituted account is turned away up front
+/// instead of failing somewhere inside a CPI.
+pub fn check_program(account: &AccountInfo, program_id: &pinocchio::pubkey::Pubkey) -> ProgramResult {
+    if account.key() != program_id {
+        return Err(FundRaiserError::InvalidProgram.into());
+    }
+    Ok(())
+}
+
+/// A hard cap has to cover the target, basis points cannot exceed 100% and the minimum has to fit
+/// under the resolved per-contributor cap.
+pub fn check_contribution_limits(fundraiser_state: &FundRaiser) -> ProgramResult {
+    if fundraiser_state.hard_cap() != 0 && fundraiser_state.hard_cap() < fundraiser_state.amount_to_raise() {
+        return Err(FundRaiserError::InvalidHardCap.into());
+    }
+    if fundraiser_state.cap_kind()? == ContributionCapKind::BasisPoints && fundraiser_state.max_per_contributor() > BASIS_POINTS_SCALER {
+        return Err(FundRaiserError::InvalidContributionLimits.into());
+    }
+    let min_contribution = fundraiser_state.min_contribution();
+    if min_contribution == 0 || min_contribution > fundraiser_state.max_contribution_amount()? {
+        return Err(FundRaiserError::InvalidContributionLimits.into());
+    }
+    Ok(())
+}
+
+/// Stretch goals climb strictly above the target and stay within the hard cap, if any.
+pub fn check_stretch_goals(fundraiser_state: &FundRaiser) -> ProgramResult {
+    let mut previous = fundraiser_state.amount_to_raise();
+    for index in 0..fundraiser_state.stretch_goal_count() {
+        let goal = fundraiser_state.stretch_goal(index).ok_or(FundRaiserError::InvalidStretchGoals)?;
+        if goal <= previous {
+            return Err(FundRaiserError::InvalidStretchGoals.into());
+        }
+        previous = goal;
+    }
+    if fundraiser_state.hard_cap() != 0 && previous > fundraiser_state.hard_cap() {
+        return Err(FundRaiserError::InvalidStretchGoals.into());
+    }
+    Ok(())
+}
+
+/// `maker` must be the maker stored in the fundraiser (used for non-signing maker accounts).
+pub fn check_maker(fundraiser_state: &FundRaiser, maker: &AccountInfo) -> ProgramResult {
+    if fundraiser_state.maker() != *maker.key() {
+        return Err(FundRaiserError::InvalidMaker.into());
+    }
+    Ok(())
+}
+
+/// For SPL campaigns, `mint` must be the stored mint and be owned by a supported `token_program`.
+pub fn check_mint(fundraiser_state: &FundRaiser, mint: &AccountInfo, token_program: &AccountInfo) -> ProgramResult {
+    if fundraiser_state.is_native() {
+        return Ok(());
+    }
+    check_token_program(token_program)?;
+    if fundraiser_state.mint_to_raise() != *mint.key() {
+        return Err(FundRaiserError::InvalidMint.into());
+    }
+    if mint.owner() != token_program.key() {
+        return Err(FundRaiserError::InvalidTokenProgram.into());
+    }
+    Ok(())
+}
+
+/// The vault is `[b"vault", fundraiser]` for native SOL campaigns and the fundraiser's ATA
+/// for `mint_to_raise` otherwise; both are checked against the bump stored at Initialize.
+pub fn check_vault(
+    fundraiser_state: &FundRaiser,
+    fundraiser: &AccountInfo,
+    vault: &AccountInfo,
+    token_program: &AccountInfo,
+) -> ProgramResult {
+    let bump = [fundraiser_state.vault_bump];
+    let (expected, expected_owner) = if fundraiser_state.is_native() {
+        (create_program_address(&[b"vault", fundraiser.key().as_ref(), &bump], &crate::ID)?, &crate::ID)
+    } else {
+        let mint = fundraiser_state.mint_to_raise();
+        (
+            create_program_address(
+                &[fundraiser.key().as_ref(), token_program.key().as_ref(), mint.as_ref(), &bump],
+                &pinocchio_associated_token_account::ID,
+            )?,
+            token_program.key(),
+        )
+    };
+    if vault.key() != &expected || vault.owner() != expected_owner {
+        return Err(FundRaiserError::InvalidVault.into());
+    }
+    Ok(())
+}
+
+/// The contributor record must be the PDA `[b"contributor", fundraiser, contributor]`.
+/// Returns its bump; an existing record must also be program-owned with the current `Contributor` layout
+/// and is checked against its stored bump, so only creating a record pays for the bump search.
+pub fn check_contributor_account(
+    contributor_account: &AccountInfo,
+    fundraiser: &AccountInfo,
+    contributor: &AccountInfo,
+) -> Result<u8, pinocchio::program_error::ProgramError> {
+    if contributor_account.data_is_empty() {
+        let (expected, bump) = find_program_address(
+            &[b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref()],
+            &crate::ID,
+        );
+        if contributor_account.key() != &expected {
+            return Err(FundRaiserError::InvalidContributorAccount.into());
+        }
+        return Ok(bump);
+    }
+    if contributor_account.owner() != &crate::ID {
+        return Err(FundRaiserError::InvalidContributorAccount.into());
+    }
+    if matches!(contributor_account.data_len(), Contributor::LEGACY_LEN | Contributor::LEN_V1 | Contributor::LEN_V2) {
+        return Err(FundRaiserError::InvalidAccountVersion.into());
+    }
+    if contributor_account.data_len() != Contributor::LEN {
+        return Err(FundRaiserError::InvalidContributorAccount.into());
+    }
+    let contributor_state = Contributor::from_account_info(contributor_account)?;
+    if contributor_state.discriminator() != Contributor::DISCRIMINATOR {
+        return Err(FundRaiserError::InvalidContributorAccount.into());
+    }
+    if contributor_state.version() != Contributor::VERSION {
+        return Err(FundRaiserError::InvalidAccountVersion.into());
+    }
+    let bump = contributor_state.bump;
+    let expected = create_program_address(
+        &[b"contributor", fundraiser.key().as_ref(), contributor.key().as_ref(), &[bump]],
+        &crate::ID,
+    ).map_err(|_| FundRaiserError::InvalidContributorAccount)?;
+    if contributor_account.key() != &expected {
+        return Err(FundRaiserError::InvalidContributorAccount.into());
+    }
+    Ok(bump)
+}
+
+/// The receipt mint must be the `[b"receipt", fundraiser]` PDA, owned by a supported `token_program`.
+pub fn check_receipt_mint(
+    fundraiser_state: &FundRaiser,
+    fundraiser: &AccountInfo,
+    receipt_mint: &AccountInfo,
+    token_program: &AccountInfo,
+) -> ProgramResult {
+    check_token_program(token_program)?;
+    let bump = [fundraiser_state.receipt_bump];
+    let expected = create_program_address(&[b"receipt", fundraiser.key().as_ref(), &bump], &crate::ID)?;
+    if receipt_mint.key() != &expected || receipt_mint.owner() != token_program.key() {
+        return Err(FundRaiserError::InvalidReceiptMint.into());
+    }
+    Ok(())
+}
+
+/// The release schedule must be the program-owned `[b"milestones", fundraiser]` PDA.
+pub fn check_milestones_account(fundraiser: &AccountInfo, milestones: &AccountInfo) -> ProgramResult {
+    if milestones.owner() != &crate::ID || milestones.data_len() != Milestones::LEN {
+        return Err(FundRaiserError::InvalidMilestones.into());
+    }
+    let bump = [Milestones::from_account_info(milestones)?.bump];
+    let expected = create_program_address(&[b"milestones", fundraiser.key().as_ref(), &bump], &crate::ID)?;
+    if milestones.key() != &expected {
+        return Err(FundRaiserError::InvalidMilestones.into());
+    }
+    Ok(())
+}
+
+/// A cancelled or voted down fundraiser can be refunded right away; otherwise it has to have ended
+/// short of its target without paying out tranches to the maker. A release whose voting closed
+/// unapproved is settled as rejected here, so refunds shrinking the quorum cannot revive it.
+pub fn check_refundable(fundraiser_state: &mut FundRaiser, now: i64) -> ProgramResult {
+    if fundraiser_state.release_lapsed(now) {
+        fundraiser_state.set_status(FundRaiserStatus::Rejected);
+    }
+    if fundraiser_state.is_cancelled() || fundraiser_state.is_rejected() {
+        return Ok(());
+    }
+    if fundraiser_state.phase(now) != FundRaiserPhase::Ended {
+        return Err(FundRaiserError::FundraiserNotEnded.into());
+    }
+    if fundraiser_state.is_releasing() || fundraiser_state.target_met() {
+        return Err(FundRaiserError::TargetMet.into());
+    }
+    Ok(())
+}
+
+/// Governed campaigns only release funds the contributors approved before voting closed.
+pub fn check_release_approved(fundraiser_state: &FundRaiser, now: i64) -> ProgramResult {
+    if !fundraiser_state.is_governed() {
+        return Ok(());
+    }
+    if fundraiser_state.is_rejected() || fundraiser_state.release_lapsed(now) {
+        return Err(FundRaiserError::ReleaseRejected.into());
+    }
+    if !fundraiser_state.release_approved() {
+        return Err(FundRaiserError::ReleaseNotApproved.into());
+    }
+    Ok(())
+}
+
+/// The program's ProgramData account must name `authority` as its upgrade authority. Its
+/// layout is the loader's `ProgramData` state: a u32 tag (3), the deploy slot (u64) and an
+/// `Option<Pubkey>` authority.
+pub fn check_upgrade_authority(program_data: &AccountInfo, authority: &AccountInfo) -> ProgramResult {
+    let (expected, _) = find_program_address(&[crate::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+    if program_data.key() != &expected || program_data.owner() != &BPF_LOADER_UPGRADEABLE_ID {
+        return Err(FundRaiserError::NotUpgradeAuthority.into());
+    }
+    let data = program_data.try_borrow_data()?;
+    if data.len() < 45 || data[0..4] != 3u32.to_le_bytes() || data[12] != 1 || data[13..45] != authority.key()[..] {
+        return Err(FundRaiserError::NotUpgradeAuthority.into());
+    }
+    Ok(())
+}
+
+/// The config must be the `[b"config"]` PDA. Returns false while it has not been created yet,
+/// in which case no platform fee applies.
+pub fn check_config_account(config: &AccountInfo) -> Result<bool, pinocchio::program_error::ProgramError> {
+    if config.data_is_empty() {
+        let (expected, _) = find_program_address(&[b"config"], &crate::ID);
+        if config.key() != &expected {
+            return Err(FundRaiserError::InvalidConfig.into());
+        }
+        return Ok(false);
+    }
+    if config.owner() != &crate::ID || config.data_len() != Config::LEN {
+        return Err(FundRaiserError::InvalidConfig.into());
+    }
+    let bump = [Config::from_account_info(config)?.bump];
+    if config.key() != &create_program_address(&[b"config", &bump], &crate::ID)? {
+        return Err(FundRaiserError::InvalidConfig.into());
+    }
+    Ok(true)
+}
+
+/// The fee goes to the configured recipient wallet for native SOL campaigns, and to that
+/// wallet's ATA for `mint_to_raise` otherwise.
+pub fn check_fee_recipient(
+    config_state: &Config,
+    fundraiser_state: &FundRaiser,
+    fee_recipient: &AccountInfo,
+    fee_recipient_ata: &AccountInfo,
+    token_program: &AccountInfo,
+) -> ProgramResult {
+    if fee_recipient.key() != &config_state.fee_recipient() {
+        return Err(FundRaiserError::InvalidFeeRecipient.into());
+    }
+    if !fundraiser_state.is_native() {
+        let mint = fundraiser_state.mint_to_raise();
+        let (expected, _) = find_program_address(
+            &[fee_recipient.key().as_ref(), token_program.key().as_ref(), mint.as_ref()],
+            &pinocchio_associated_token_account::ID,
+        );
+        if fee_recipient_ata.key() != &expected {
+            return Err(FundRaiserError::InvalidFeeRecipient.into());
+        }
+    }
+    Ok(())
+}